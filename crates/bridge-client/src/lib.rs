@@ -0,0 +1,729 @@
+//! Client side of the zk-bridge program.
+//!
+//! The program lives in its own Anchor workspace under `onchain`, so the seeds,
+//! argument layouts and instruction discriminators it uses are mirrored here.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use onchain_types::{PublicValues, ZK_BRIDGE_VKEY_HASH};
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::{hash, Hash},
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_program,
+    transaction::Transaction,
+};
+use std::fmt;
+
+pub mod watcher;
+
+solana_sdk::declare_id!("Bq5sTpeHWMCety13tmZqgYvDJoAAC4mAwBn33mZmuc41");
+
+pub const PLATFORM_SEED_PREFIX: &[u8] = b"platform";
+pub const RAMP_PAGE_SEED_PREFIX: &[u8] = b"ramp_page";
+pub const RAMP_PAGE_CAPACITY: u64 = 32;
+pub const BATCH_SEED_PREFIX: &[u8] = b"batch";
+pub const BATCH_DATA_SEED_PREFIX: &[u8] = b"batch_data";
+pub const MESSAGE_SEED_PREFIX: &[u8] = b"message";
+pub const WITHDRAWAL_CLAIM_SEED_PREFIX: &[u8] = b"withdrawal_claim";
+
+/// Offset of the data in a `BatchData` account, after the discriminator and
+/// the header
+pub const BATCH_DATA_OFFSET: usize = 8 + 32 + 32 + 32 + 8 + 8 + 1 + 1;
+
+/// Batch data sent per `publish_batch_data` transaction, small enough to fit
+/// with the signature and accounts in a transaction
+pub const BATCH_DATA_CHUNK_SIZE: usize = 900;
+
+/// Groth16 verification does not fit in the default compute budget
+pub const PROVE_COMPUTE_UNITS: u32 = 1_400_000;
+
+pub fn platform_address(platform_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PLATFORM_SEED_PREFIX, platform_id.as_ref()], &ID).0
+}
+
+pub fn ramp_page_address(platform_id: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            RAMP_PAGE_SEED_PREFIX,
+            platform_id.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+pub fn batch_address(platform_id: &Pubkey, batch_number: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            BATCH_SEED_PREFIX,
+            platform_id.as_ref(),
+            &batch_number.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+pub fn message_address(platform_id: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            MESSAGE_SEED_PREFIX,
+            platform_id.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+pub fn withdrawal_claim_address(platform_id: &Pubkey, batch_number: u64, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            WITHDRAWAL_CLAIM_SEED_PREFIX,
+            platform_id.as_ref(),
+            &batch_number.to_le_bytes(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+pub fn batch_data_address(platform_id: &Pubkey, publisher: &Pubkey, txs_hash: &Hash) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            BATCH_DATA_SEED_PREFIX,
+            platform_id.as_ref(),
+            publisher.as_ref(),
+            txs_hash.as_ref(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+#[derive(Debug)]
+pub enum BridgeClientError {
+    Rpc(ClientError),
+    AccountNotFound(Pubkey),
+    InvalidAccount(Pubkey),
+    InvalidPublicValues,
+    InvalidProof,
+    /// The queues read do not hash to those of the platform, read from
+    /// another fork
+    QueueMismatch(Pubkey),
+}
+
+impl fmt::Display for BridgeClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeClientError::Rpc(err) => write!(f, "rpc error: {}", err),
+            BridgeClientError::AccountNotFound(address) => {
+                write!(f, "account {} not found", address)
+            }
+            BridgeClientError::InvalidAccount(address) => {
+                write!(f, "account {} is not a valid zk-bridge account", address)
+            }
+            BridgeClientError::InvalidPublicValues => write!(f, "invalid public values"),
+            BridgeClientError::InvalidProof => {
+                write!(f, "proof does not verify against {}", ZK_BRIDGE_VKEY_HASH)
+            }
+            BridgeClientError::QueueMismatch(platform_id) => {
+                write!(
+                    f,
+                    "queues do not match the hashes of platform {}",
+                    platform_id
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BridgeClientError {}
+
+impl From<ClientError> for BridgeClientError {
+    fn from(err: ClientError) -> Self {
+        BridgeClientError::Rpc(err)
+    }
+}
+
+/// Mirror of a zk-bridge account, stored after its Anchor discriminator
+pub trait BridgeAccount: BorshDeserialize {
+    const NAME: &'static str;
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct Platform {
+    pub sequencer: Pubkey,
+    pub id: Pubkey,
+    pub last_state_hash: [u8; 32],
+    pub config_hash: [u8; 32],
+    pub ramp_head: u64,
+    pub ramp_tail: u64,
+    pub ramp_head_hash: [u8; 32],
+    pub ramp_txs_hash: [u8; 32],
+    pub deposit: u64,
+    pub withdraw: u64,
+    pub message_head: u64,
+    pub message_tail: u64,
+    pub message_head_hash: [u8; 32],
+    pub message_queue_hash: [u8; 32],
+    pub batch_number: u64,
+    pub bump: u8,
+}
+
+impl BridgeAccount for Platform {
+    const NAME: &'static str = "Platform";
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct Batch {
+    pub number: u64,
+    pub state_hash: [u8; 32],
+    pub txs_hash: [u8; 32],
+    pub outbox_root: [u8; 32],
+    pub withdrawals_root: [u8; 32],
+    pub ramp_head: u64,
+    pub message_head: u64,
+    pub batch_data: Pubkey,
+    pub bump: u8,
+}
+
+impl BridgeAccount for Batch {
+    const NAME: &'static str = "Batch";
+}
+
+/// Header of a `BatchData` account, the data follows it at [`BATCH_DATA_OFFSET`]
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct BatchData {
+    pub platform: Pubkey,
+    pub publisher: Pubkey,
+    pub txs_hash: [u8; 32],
+    pub size: u64,
+    pub written: u64,
+    pub sealed: bool,
+    pub bump: u8,
+}
+
+impl BridgeAccount for BatchData {
+    const NAME: &'static str = "BatchData";
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct RampTx {
+    pub is_onramp: bool,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub mint: Option<Pubkey>,
+    pub decimals: u8,
+}
+
+impl From<RampTx> for svm_runner_types::RampTx {
+    fn from(ramp_tx: RampTx) -> Self {
+        svm_runner_types::RampTx {
+            is_onramp: ramp_tx.is_onramp,
+            user: ramp_tx.user,
+            amount: ramp_tx.amount,
+            mint: ramp_tx.mint,
+            decimals: ramp_tx.decimals,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct RampEntry {
+    pub ramp_tx: RampTx,
+    pub hash: [u8; 32],
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct RampPage {
+    pub platform: Pubkey,
+    pub index: u64,
+    pub entries: Vec<RampEntry>,
+    pub bump: u8,
+}
+
+impl BridgeAccount for RampPage {
+    const NAME: &'static str = "RampPage";
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct MessageAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(BorshDeserialize, Debug, Clone)]
+pub struct Message {
+    pub platform: Pubkey,
+    pub index: u64,
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<MessageAccountMeta>,
+    pub data: Vec<u8>,
+    pub hash: [u8; 32],
+    pub bump: u8,
+}
+
+impl BridgeAccount for Message {
+    const NAME: &'static str = "Message";
+}
+
+impl From<Message> for svm_runner_types::L1Message {
+    fn from(message: Message) -> Self {
+        svm_runner_types::L1Message {
+            sender: message.sender,
+            program_id: message.program_id,
+            accounts: message
+                .accounts
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: message.data,
+        }
+    }
+}
+
+/// Fetches and decodes a zk-bridge account.
+pub fn get_account<T: BridgeAccount>(
+    client: &RpcClient,
+    address: &Pubkey,
+) -> Result<T, BridgeClientError> {
+    get_account_with_slot(client, address, CommitmentConfig::confirmed())
+        .map(|(_, account)| account)
+}
+
+/// Fetches and decodes a zk-bridge account, along with the slot it was read at.
+pub fn get_account_with_slot<T: BridgeAccount>(
+    client: &RpcClient,
+    address: &Pubkey,
+    commitment: CommitmentConfig,
+) -> Result<(u64, T), BridgeClientError> {
+    let response = client.get_account_with_commitment(address, commitment)?;
+    let slot = response.context.slot;
+    let account = response
+        .value
+        .ok_or(BridgeClientError::AccountNotFound(*address))?;
+
+    if account.owner != ID || !account.data.starts_with(&account_discriminator(T::NAME)) {
+        return Err(BridgeClientError::InvalidAccount(*address));
+    }
+    // Accounts can be larger than their content, so trailing bytes are ignored
+    let account = T::deserialize(&mut &account.data[8..])
+        .map_err(|_| BridgeClientError::InvalidAccount(*address))?;
+    Ok((slot, account))
+}
+
+/// The data of a sealed `BatchData` account, the bincode encoded transactions
+/// of a batch.
+pub fn get_batch_data(client: &RpcClient, address: &Pubkey) -> Result<Vec<u8>, BridgeClientError> {
+    let batch_data: BatchData = get_account(client, address)?;
+    if !batch_data.sealed {
+        return Err(BridgeClientError::InvalidAccount(*address));
+    }
+    let account = client.get_account_with_commitment(address, CommitmentConfig::confirmed())?;
+    let data = account
+        .value
+        .ok_or(BridgeClientError::AccountNotFound(*address))?
+        .data;
+    data.get(BATCH_DATA_OFFSET..BATCH_DATA_OFFSET + batch_data.size as usize)
+        .map(|data| data.to_vec())
+        .ok_or(BridgeClientError::InvalidAccount(*address))
+}
+
+/// Ramp txs `start..start + count` of the ramp queue of a platform.
+pub fn get_ramp_txs(
+    client: &RpcClient,
+    platform_id: &Pubkey,
+    start: u64,
+    count: u64,
+) -> Result<Vec<RampTx>, BridgeClientError> {
+    get_ramp_txs_with_commitment(
+        client,
+        platform_id,
+        start,
+        count,
+        CommitmentConfig::confirmed(),
+    )
+}
+
+/// [`get_ramp_txs`] read at `commitment`.
+pub fn get_ramp_txs_with_commitment(
+    client: &RpcClient,
+    platform_id: &Pubkey,
+    start: u64,
+    count: u64,
+    commitment: CommitmentConfig,
+) -> Result<Vec<RampTx>, BridgeClientError> {
+    let mut ramp_txs = Vec::with_capacity(count as usize);
+    let mut page: Option<RampPage> = None;
+
+    for index in start..start + count {
+        let page_index = index / RAMP_PAGE_CAPACITY;
+        if page.as_ref().map(|page| page.index) != Some(page_index) {
+            let address = ramp_page_address(platform_id, page_index);
+            page = Some(get_account_with_slot(client, &address, commitment)?.1);
+        }
+        let page = page.as_ref().unwrap();
+        let entry = page
+            .entries
+            .get((index % RAMP_PAGE_CAPACITY) as usize)
+            .ok_or(BridgeClientError::InvalidAccount(ramp_page_address(
+                platform_id,
+                page_index,
+            )))?;
+        ramp_txs.push(entry.ramp_tx.clone());
+    }
+
+    Ok(ramp_txs)
+}
+
+/// L1 messages `start..start + count` sent to a platform.
+pub fn get_messages(
+    client: &RpcClient,
+    platform_id: &Pubkey,
+    start: u64,
+    count: u64,
+) -> Result<Vec<Message>, BridgeClientError> {
+    get_messages_with_commitment(
+        client,
+        platform_id,
+        start,
+        count,
+        CommitmentConfig::confirmed(),
+    )
+}
+
+/// [`get_messages`] read at `commitment`.
+pub fn get_messages_with_commitment(
+    client: &RpcClient,
+    platform_id: &Pubkey,
+    start: u64,
+    count: u64,
+    commitment: CommitmentConfig,
+) -> Result<Vec<Message>, BridgeClientError> {
+    (start..start + count)
+        .map(|index| {
+            let address = message_address(platform_id, index);
+            get_account_with_slot(client, &address, commitment).map(|(_, message)| message)
+        })
+        .collect()
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct CreatePlatformArgs {
+    pub id: Pubkey,
+    pub initial_state_hash: [u8; 32],
+    pub config_hash: [u8; 32],
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct AddRampTxArgs {
+    pub is_onramp: bool,
+    pub amount: u64,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct WithdrawArgs {
+    pub batch_number: u64,
+    /// Position of the withdrawal in the batch
+    pub index: u64,
+    pub amount: u64,
+    /// Merkle proof of the withdrawal against the withdrawals root of the batch
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct PublishBatchDataArgs {
+    pub txs_hash: [u8; 32],
+    pub size: u64,
+    pub data: Vec<u8>,
+}
+
+#[derive(BorshSerialize, Debug)]
+pub struct ProveArgs {
+    pub proof: Vec<u8>,
+    pub public_values: Vec<u8>,
+}
+
+pub fn create_platform(sequencer: &Pubkey, args: &CreatePlatformArgs) -> Instruction {
+    anchor_instruction(
+        "create_platform",
+        args,
+        vec![
+            AccountMeta::new(*sequencer, true),
+            AccountMeta::new(platform_address(&args.id), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `ramp_page_index` is the page of the ramp queue tail, `ramp_tail / RAMP_PAGE_CAPACITY`.
+pub fn add_ramp_tx(
+    platform_id: &Pubkey,
+    ramper: &Pubkey,
+    ramp_page_index: u64,
+    args: &AddRampTxArgs,
+) -> Instruction {
+    anchor_instruction(
+        "add_ramp_tx",
+        args,
+        vec![
+            AccountMeta::new(*ramper, true),
+            AccountMeta::new(platform_address(platform_id), false),
+            AccountMeta::new(ramp_page_address(platform_id, ramp_page_index), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn withdraw(platform_id: &Pubkey, ramper: &Pubkey, args: &WithdrawArgs) -> Instruction {
+    anchor_instruction(
+        "withdraw",
+        args,
+        vec![
+            AccountMeta::new(*ramper, true),
+            AccountMeta::new(platform_address(platform_id), false),
+            AccountMeta::new_readonly(batch_address(platform_id, args.batch_number), false),
+            AccountMeta::new(
+                withdrawal_claim_address(platform_id, args.batch_number, args.index),
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn publish_batch_data(
+    platform_id: &Pubkey,
+    publisher: &Pubkey,
+    args: &PublishBatchDataArgs,
+) -> Instruction {
+    let txs_hash = Hash::new_from_array(args.txs_hash);
+    anchor_instruction(
+        "publish_batch_data",
+        args,
+        vec![
+            AccountMeta::new(*publisher, true),
+            AccountMeta::new_readonly(platform_address(platform_id), false),
+            AccountMeta::new(batch_data_address(platform_id, publisher, &txs_hash), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Closes the unsealed batch data of `publisher` for `txs_hash`.
+pub fn close_batch_data(platform_id: &Pubkey, publisher: &Pubkey, txs_hash: &Hash) -> Instruction {
+    anchor_instruction(
+        "close_batch_data",
+        &(),
+        vec![
+            AccountMeta::new(*publisher, true),
+            AccountMeta::new_readonly(platform_address(platform_id), false),
+            AccountMeta::new(batch_data_address(platform_id, publisher, txs_hash), false),
+        ],
+    )
+}
+
+/// `ramp_page` is the page holding the last ramp tx consumed by the batch, and
+/// `last_message` the last L1 message it executed, if any.
+pub fn prove(
+    platform_id: &Pubkey,
+    prover: &Pubkey,
+    batch_number: u64,
+    batch_data: &Pubkey,
+    ramp_page: Option<Pubkey>,
+    last_message: Option<Pubkey>,
+    args: &ProveArgs,
+) -> Instruction {
+    anchor_instruction(
+        "prove",
+        args,
+        vec![
+            AccountMeta::new(*prover, true),
+            AccountMeta::new(platform_address(platform_id), false),
+            AccountMeta::new(batch_address(platform_id, batch_number), false),
+            AccountMeta::new_readonly(*batch_data, false),
+            // Anchor reads the program id as a missing optional account
+            AccountMeta::new_readonly(ramp_page.unwrap_or(ID), false),
+            AccountMeta::new_readonly(last_message.unwrap_or(ID), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Publishes `data`, the bincode encoded transactions of a batch, and returns
+/// the sealed `BatchData` account. A partial publication is resumed.
+pub fn send_batch_data(
+    client: &RpcClient,
+    publisher: &Keypair,
+    platform_id: &Pubkey,
+    data: &[u8],
+) -> Result<Pubkey, BridgeClientError> {
+    let txs_hash = solana_sdk::hash::hashv(&[data]);
+    let address = batch_data_address(platform_id, &publisher.pubkey(), &txs_hash);
+
+    // Batches with the same transactions share their data
+    let published = match get_account::<BatchData>(client, &address) {
+        Ok(batch_data) if batch_data.sealed => return Ok(address),
+        Ok(batch_data) => batch_data.written as usize,
+        Err(BridgeClientError::AccountNotFound(_)) => 0,
+        Err(e) => return Err(e),
+    };
+
+    for chunk in data[published..].chunks(BATCH_DATA_CHUNK_SIZE) {
+        let instruction = publish_batch_data(
+            platform_id,
+            &publisher.pubkey(),
+            &PublishBatchDataArgs {
+                txs_hash: txs_hash.to_bytes(),
+                size: data.len() as u64,
+                data: chunk.to_vec(),
+            },
+        );
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&publisher.pubkey()),
+            &[publisher],
+            client.get_latest_blockhash()?,
+        );
+        client.send_and_confirm_transaction(&transaction)?;
+    }
+
+    Ok(address)
+}
+
+/// Checks the proof and public values of `prove` the way zk-bridge does,
+/// against [`ZK_BRIDGE_VKEY_HASH`] and the SP1 Groth16 verifying key, so an
+/// invalid proof is caught before paying for the transaction.
+pub fn verify_onchain_proof(args: &ProveArgs) -> Result<(), BridgeClientError> {
+    sp1_solana::verify_proof(
+        &args.proof,
+        &args.public_values,
+        ZK_BRIDGE_VKEY_HASH,
+        sp1_solana::GROTH16_VK_4_0_0_RC3_BYTES,
+    )
+    .map_err(|_| BridgeClientError::InvalidProof)?;
+    PublicValues::from_bytes(&args.public_values).ok_or(BridgeClientError::InvalidPublicValues)?;
+    Ok(())
+}
+
+/// Calls `prove` with the proof of the next batch of a platform, whose batch
+/// data is published at `batch_data`.
+pub fn send_proof(
+    client: &RpcClient,
+    prover: &Keypair,
+    platform_id: &Pubkey,
+    batch_data: &Pubkey,
+    args: &ProveArgs,
+) -> Result<Signature, BridgeClientError> {
+    let platform: Platform = get_account(client, &platform_address(platform_id))?;
+    let values = PublicValues::from_bytes(&args.public_values)
+        .ok_or(BridgeClientError::InvalidPublicValues)?;
+    let ramp_page = (values.ramp_txs_count > 0).then(|| {
+        let last = platform.ramp_head + values.ramp_txs_count - 1;
+        ramp_page_address(platform_id, last / RAMP_PAGE_CAPACITY)
+    });
+    let last_message = (values.l1_messages_count > 0).then(|| {
+        message_address(
+            platform_id,
+            platform.message_head + values.l1_messages_count - 1,
+        )
+    });
+
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(PROVE_COMPUTE_UNITS),
+        prove(
+            platform_id,
+            &prover.pubkey(),
+            platform.batch_number,
+            batch_data,
+            ramp_page,
+            last_message,
+            args,
+        ),
+    ];
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&prover.pubkey()),
+        &[prover],
+        client.get_latest_blockhash()?,
+    );
+    Ok(client.send_and_confirm_transaction(&transaction)?)
+}
+
+/// First 8 bytes of the data of an Anchor account.
+fn account_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(format!("account:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// First 8 bytes of the instruction data of an Anchor instruction.
+fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{}", name).as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+fn anchor_instruction(
+    name: &str,
+    args: &impl BorshSerialize,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut data = instruction_discriminator(name).to_vec();
+    args.serialize(&mut data).unwrap();
+
+    Instruction {
+        program_id: ID,
+        accounts,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instruction_discriminator() {
+        // Discriminator of the `initialize` instruction of every new Anchor project
+        assert_eq!(
+            instruction_discriminator("initialize"),
+            [175, 175, 109, 31, 13, 152, 155, 237]
+        );
+    }
+
+    #[test]
+    fn test_batch_data_offset() {
+        // The header ends exactly where the data starts
+        let header = [0; BATCH_DATA_OFFSET - 8];
+        let mut remaining = &header[..];
+        BatchData::deserialize(&mut remaining).unwrap();
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn test_verify_onchain_proof() {
+        let args = ProveArgs {
+            proof: vec![0; 260],
+            public_values: PublicValues::default().to_bytes(),
+        };
+        assert!(matches!(
+            verify_onchain_proof(&args),
+            Err(BridgeClientError::InvalidProof)
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+//! Reads what the next proof of a platform must use from L1.
+//!
+//! `prove` checks the public values of a proof against the `Platform` account,
+//! so the pre-state, the ramp txs and the L1 messages of a batch have to be
+//! taken from it rather than built locally.
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash, pubkey::Pubkey};
+use std::{thread, time::Duration};
+use svm_runner_types::{hash_l1_messages, hash_ramp_txs, L1Message, RampTx};
+
+use crate::{
+    get_account_with_slot, get_messages_with_commitment, get_ramp_txs_with_commitment,
+    platform_address, BridgeClientError, Platform,
+};
+
+/// Inputs of the next batch of a platform, as read from L1.
+#[derive(Debug, Clone)]
+pub struct NextBatch {
+    /// Slot the platform was read at
+    pub slot: u64,
+    pub batch_number: u64,
+    /// Config hash the proof must commit to, `svm_runner::config_hash` of
+    /// the runner the platform was created for
+    pub config_hash: Hash,
+    /// State the proof must start from, `PublicValues::pre_state_hash`
+    pub pre_state_hash: Hash,
+    /// Ramp txs hash of the queue before `ramp_txs`
+    pub ramp_txs_start_hash: Hash,
+    /// Pending ramp txs, oldest first. A batch consumes a prefix of them.
+    pub ramp_txs: Vec<RampTx>,
+    /// Message queue hash before `l1_messages`
+    pub l1_messages_start_hash: Hash,
+    /// Pending L1 messages, oldest first. A batch executes a prefix of them.
+    pub l1_messages: Vec<L1Message>,
+}
+
+/// Watches the `Platform` account of a platform, only reporting a state once
+/// `confirmations` slots were produced on top of it.
+///
+/// A slot height alone does not show the fork survived, so a platform is read
+/// at `confirmed` commitment without confirmations and at `finalized`
+/// otherwise, and the queues read must hash to those of the platform.
+pub struct RampWatcher<'a> {
+    client: &'a RpcClient,
+    platform_id: Pubkey,
+    confirmations: u64,
+    /// Commitment of every read, the queues are read up to the tails of the
+    /// platform so they must be read at the same one
+    commitment: CommitmentConfig,
+    poll_interval: Duration,
+}
+
+impl<'a> RampWatcher<'a> {
+    pub fn new(client: &'a RpcClient, platform_id: Pubkey, confirmations: u64) -> Self {
+        RampWatcher {
+            client,
+            platform_id,
+            confirmations,
+            commitment: if confirmations == 0 {
+                CommitmentConfig::confirmed()
+            } else {
+                CommitmentConfig::finalized()
+            },
+            // Half a slot
+            poll_interval: Duration::from_millis(200),
+        }
+    }
+
+    /// Waits for the platform to be `confirmations` slots deep, returns it and
+    /// the slot it was read at.
+    pub fn confirmed_platform(&self) -> Result<(u64, Platform), BridgeClientError> {
+        let address = platform_address(&self.platform_id);
+        let (mut slot, mut platform) =
+            get_account_with_slot::<Platform>(self.client, &address, self.commitment)?;
+
+        loop {
+            while self.client.get_slot_with_commitment(self.commitment)? < slot + self.confirmations
+            {
+                thread::sleep(self.poll_interval);
+            }
+
+            // Still on the same fork: nothing was proven in between, and the
+            // queues only grew. Otherwise start over from the current state.
+            let (current_slot, current) =
+                get_account_with_slot::<Platform>(self.client, &address, self.commitment)?;
+            if current.batch_number == platform.batch_number
+                && current.last_state_hash == platform.last_state_hash
+                && current.ramp_tail >= platform.ramp_tail
+                && current.message_tail >= platform.message_tail
+            {
+                return Ok((slot, platform));
+            }
+            (slot, platform) = (current_slot, current);
+        }
+    }
+
+    /// Inputs of the next batch once they are `confirmations` slots deep.
+    pub fn next_batch(&self) -> Result<NextBatch, BridgeClientError> {
+        let (slot, platform) = self.confirmed_platform()?;
+
+        let ramp_txs = get_ramp_txs_with_commitment(
+            self.client,
+            &self.platform_id,
+            platform.ramp_head,
+            platform.ramp_tail - platform.ramp_head,
+            self.commitment,
+        )?;
+        let l1_messages = get_messages_with_commitment(
+            self.client,
+            &self.platform_id,
+            platform.message_head,
+            platform.message_tail - platform.message_head,
+            self.commitment,
+        )?;
+        let ramp_txs: Vec<RampTx> = ramp_txs.into_iter().map(Into::into).collect();
+        let l1_messages: Vec<L1Message> = l1_messages.into_iter().map(Into::into).collect();
+
+        let ramp_txs_start_hash = Hash::new_from_array(platform.ramp_head_hash);
+        let l1_messages_start_hash = Hash::new_from_array(platform.message_head_hash);
+        if hash_ramp_txs(ramp_txs_start_hash, &ramp_txs).to_bytes() != platform.ramp_txs_hash
+            || hash_l1_messages(l1_messages_start_hash, &l1_messages).to_bytes()
+                != platform.message_queue_hash
+        {
+            return Err(BridgeClientError::QueueMismatch(self.platform_id));
+        }
+
+        Ok(NextBatch {
+            slot,
+            batch_number: platform.batch_number,
+            config_hash: Hash::new_from_array(platform.config_hash),
+            pre_state_hash: Hash::new_from_array(platform.last_state_hash),
+            ramp_txs_start_hash,
+            ramp_txs,
+            l1_messages_start_hash,
+            l1_messages,
+        })
+    }
+}
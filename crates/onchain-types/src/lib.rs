@@ -1,38 +1,163 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
-pub struct Pubkey(pub [u8; 32]);
+pub mod merkle;
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
-pub struct Account {
-    pub lamports: u64,
-    pub data: Vec<u8>,
-    pub owner: Pubkey,
-    pub executable: bool,
-    pub rent_epoch: u64,
-}
+pub struct Pubkey(pub [u8; 32]);
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct RampTx {
     pub is_onramp: bool,
     pub user: Pubkey,
     pub amount: u64,
+    /// L1 mint of a token ramp, `None` for native SOL
+    pub mint: Option<Pubkey>,
+    pub decimals: u8,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct ExecutionInput {
-    pub accounts: RollupState,
-    pub txs: Vec<u8>,
-    pub ramp_txs: Vec<RampTx>,
+impl RampTx {
+    /// Bytes folded into the ramp txs hash, `hashv([ramp_txs_hash, bytes])`,
+    /// by both zk-bridge and the guest. The hash starts from zeros.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct RollupState(pub Vec<(Pubkey, Account)>);
+/// Funds a batch releases on L1, claimed by `user` with zk-bridge `withdraw`
+/// or `withdraw_token`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Withdrawal {
+    pub user: Pubkey,
+    pub amount: u64,
+    /// L1 mint of the tokens, `None` for native SOL
+    pub mint: Option<Pubkey>,
+}
 
-pub type ExecutionOutput = [u8; 32];
+impl Withdrawal {
+    /// Leaf of the withdrawal at position `index` in the withdrawals Merkle tree.
+    pub fn leaf_hash(&self, hashv: merkle::Hashv, index: u64) -> [u8; 32] {
+        merkle::leaf_hash(hashv, index, &bincode::serialize(self).unwrap())
+    }
+}
 
-#[derive(Deserialize, Serialize, Debug)]
-pub struct CommittedValues {
-    pub input: ExecutionInput,
-    pub output: ExecutionOutput,
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An instruction queued on L1 by `sender`, executed on L2 at the start of the next batch
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct L1Message {
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl L1Message {
+    /// Bytes folded into the message queue hash, `hashv([queue_hash, bytes])`,
+    /// by both zk-bridge and the guest. The queue hash starts from zeros.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+}
+
+/// An instruction written to the L2 outbox by `sender`, executed on L1 by zk-bridge
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct L2Message {
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl L2Message {
+    /// Leaf of the message at position `index` in the outbox Merkle tree.
+    pub fn leaf_hash(&self, hashv: merkle::Hashv, index: u64) -> [u8; 32] {
+        merkle::leaf_hash(hashv, index, &bincode::serialize(self).unwrap())
+    }
+}
+
+/// Verifying key hash of the zk-svm program that zk-bridge `prove` accepts
+/// proofs of, derived as follows:
+///
+/// ```ignore
+/// let client = sp1_sdk::ProverClient::from_env();
+/// let (pk, vk) = client.setup(ZK_SVM_ELF);
+/// let vkey_hash = vk.bytes32();
+/// ```
+///
+/// It changes with every change to the program, `cargo run -- vkey` in
+/// `script` prints the current one and fails when it differs, as does
+/// `script/tests/vkey.rs`.
+pub const ZK_BRIDGE_VKEY_HASH: &str =
+    "0x00e6c119f877ce29467d89e62b47f983177b85fddbd90ce988b6303b2f5d7f9b";
+
+/// Public values the guest commits for a batch and zk-bridge `prove` checks.
+///
+/// Every field has a fixed size, so the bincode encoding is always
+/// [`PublicValues::SIZE`] bytes: hashes are raw 32 bytes and integers are little
+/// endian `u64`, in declaration order.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq, Clone, Default)]
+pub struct PublicValues {
+    /// State hash the batch starts from, which must be the last one proven
+    pub pre_state_hash: [u8; 32],
+    pub post_state_hash: [u8; 32],
+    /// Ramp txs hash of the queue before the consumed ramp txs
+    pub ramp_txs_start_hash: [u8; 32],
+    /// Ramp txs hash of the queue after the consumed ramp txs
+    pub ramp_txs_hash: [u8; 32],
+    /// Number of ramp txs consumed from the queue
+    pub ramp_txs_count: u64,
+    /// Message queue hash before the executed L1 messages
+    pub l1_messages_start_hash: [u8; 32],
+    /// Message queue hash after the executed L1 messages
+    pub l1_messages_hash: [u8; 32],
+    /// Number of L1 messages executed from the queue
+    pub l1_messages_count: u64,
+    /// Hash of the bincode encoded transactions of the batch
+    pub txs_hash: [u8; 32],
+    /// Merkle root of the messages written to the L2 outbox during the batch
+    pub outbox_root: [u8; 32],
+    /// Merkle root of the withdrawals released by the batch
+    pub withdrawals_root: [u8; 32],
+    pub batch_number: u64,
+    /// Hash identifying the rollup configuration the batch was executed with
+    pub config_hash: [u8; 32],
+}
+
+impl PublicValues {
+    pub const SIZE: usize = 10 * 32 + 3 * 8;
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+    /// Returns `None` unless `bytes` is exactly [`PublicValues::SIZE`] long.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::SIZE {
+            return None;
+        }
+        bincode::deserialize(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_values_size() {
+        let public_values = PublicValues {
+            ramp_txs_count: u64::MAX,
+            ..Default::default()
+        };
+        let bytes = public_values.to_bytes();
+
+        assert_eq!(bytes.len(), PublicValues::SIZE);
+        assert_eq!(PublicValues::from_bytes(&bytes), Some(public_values));
+        assert_eq!(PublicValues::from_bytes(&bytes[1..]), None);
+    }
 }
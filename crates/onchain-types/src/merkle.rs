@@ -0,0 +1,101 @@
+//! Binary Merkle tree committing to the L2 outbox of a batch.
+//!
+//! The tree is generic over the hash function so that the guest and zk-bridge
+//! can each use their own `hashv`. Leaves are padded with zeros up to a power
+//! of two, and the root of an empty tree is zeros.
+
+pub type Hashv = fn(&[&[u8]]) -> [u8; 32];
+
+/// Leaf of the entry `data` at position `index`.
+pub fn leaf_hash(hashv: Hashv, index: u64, data: &[u8]) -> [u8; 32] {
+    hashv(&[&[0], &index.to_le_bytes(), data])
+}
+
+fn node_hash(hashv: Hashv, left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[1], left, right])
+}
+
+fn next_level(hashv: Hashv, level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| node_hash(hashv, &pair[0], &pair[1]))
+        .collect()
+}
+
+fn padded(leaves: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    let mut level = leaves.to_vec();
+    level.resize(leaves.len().next_power_of_two(), [0; 32]);
+    level
+}
+
+pub fn root(hashv: Hashv, leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0; 32];
+    }
+
+    let mut level = padded(leaves);
+    while level.len() > 1 {
+        level = next_level(hashv, &level);
+    }
+    level[0]
+}
+
+/// Siblings of the leaf at `index`, from the bottom of the tree up.
+pub fn proof(hashv: Hashv, leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut level = padded(leaves);
+    while level.len() > 1 {
+        proof.push(level[index ^ 1]);
+        level = next_level(hashv, &level);
+        index /= 2;
+    }
+    proof
+}
+
+pub fn verify(
+    hashv: Hashv,
+    leaf: [u8; 32],
+    mut index: u64,
+    proof: &[[u8; 32]],
+    root: &[u8; 32],
+) -> bool {
+    let mut node = leaf;
+    for sibling in proof {
+        node = if index % 2 == 0 {
+            node_hash(hashv, &node, sibling)
+        } else {
+            node_hash(hashv, sibling, &node)
+        };
+        index /= 2;
+    }
+    index == 0 && node == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Not a real hash, enough to check that proofs and roots agree
+    fn xor_hashv(vals: &[&[u8]]) -> [u8; 32] {
+        let mut out = [0; 32];
+        for (i, byte) in vals.iter().flat_map(|val| val.iter()).enumerate() {
+            out[i % 32] = out[i % 32].rotate_left(3) ^ byte;
+        }
+        out
+    }
+
+    #[test]
+    fn test_proofs() {
+        let leaves: Vec<[u8; 32]> = (0..5)
+            .map(|i| leaf_hash(xor_hashv, i, &[i as u8; 10]))
+            .collect();
+        let root = root(xor_hashv, &leaves);
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = proof(xor_hashv, &leaves, index);
+            assert_eq!(proof.len(), 3);
+            assert!(verify(xor_hashv, *leaf, index as u64, &proof, &root));
+            assert!(!verify(xor_hashv, *leaf, index as u64 + 1, &proof, &root));
+        }
+    }
+}
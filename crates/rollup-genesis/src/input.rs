@@ -0,0 +1,374 @@
+//! Human-editable form of an [`ExecutionInput`].
+//!
+//! Pubkeys and hashes are base58, account and instruction data base64. The
+//! `state` takes the same entries as a genesis file. Transactions are either
+//! pre-signed, as base64 bincode, or instructions signed with keypair files,
+//! the first signer paying the fees. Relative paths are resolved against the
+//! directory containing the file.
+//!
+//! ```json
+//! {
+//!   "batch_number": 0,
+//!   "state": {
+//!     "accounts": [{ "keypair": "payer.json", "lamports": 1000000000 }]
+//!   },
+//!   "txs": [
+//!     { "base64": "AQAB..." },
+//!     {
+//!       "instructions": [{
+//!         "program_id": "11111111111111111111111111111111",
+//!         "accounts": [
+//!           { "keypair": "payer.json", "is_signer": true, "is_writable": true },
+//!           { "pubkey": "BV97kHxvg2QpVgkuiWBJ2rESdJd3Gj8uK8aJ9kEB9oPd", "is_writable": true }
+//!         ],
+//!         "data": "AgAAAOgDAAAAAAAA"
+//!       }],
+//!       "signers": ["payer.json"]
+//!     }
+//!   ],
+//!   "ramp_txs": [
+//!     { "is_onramp": true, "user": "BV97kHxvg2QpVgkuiWBJ2rESdJd3Gj8uK8aJ9kEB9oPd", "amount": 1000 }
+//!   ]
+//! }
+//! ```
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    signature::Keypair,
+    signer::Signer,
+    transaction::Transaction,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use svm_runner_types::{ExecutionInput, L1Message, RampTx};
+
+use crate::{parse_pubkey, read_keypair, resolve_address, GenesisConfig, GenesisError};
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct InputConfig {
+    #[serde(default)]
+    pub batch_number: u64,
+    #[serde(default)]
+    pub state: GenesisConfig,
+    #[serde(default)]
+    pub txs: Vec<TransactionConfig>,
+    /// Base58 ramp txs hash of the queue before `ramp_txs`, the default hash if omitted
+    pub ramp_txs_start_hash: Option<String>,
+    #[serde(default)]
+    pub ramp_txs: Vec<RampTxConfig>,
+    /// Base58 message queue hash of the queue before `l1_messages`, the default hash if omitted
+    pub l1_messages_start_hash: Option<String>,
+    #[serde(default)]
+    pub l1_messages: Vec<L1MessageConfig>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum TransactionConfig {
+    Signed {
+        /// Base64 bincode transaction
+        base64: String,
+    },
+    Unsigned {
+        instructions: Vec<InstructionConfig>,
+        /// Keypair files (JSON byte arrays) signing the transaction, the first one pays the fees
+        signers: Vec<PathBuf>,
+        /// Base58 blockhash, the default hash if omitted
+        recent_blockhash: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct InstructionConfig {
+    /// Base58 program id
+    pub program_id: String,
+    #[serde(default)]
+    pub accounts: Vec<AccountMetaConfig>,
+    /// Base64 encoded instruction data
+    pub data: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AccountMetaConfig {
+    /// Base58 address of the account
+    pub pubkey: Option<String>,
+    /// Keypair file (JSON byte array) the address is taken from
+    pub keypair: Option<PathBuf>,
+    #[serde(default)]
+    pub is_signer: bool,
+    #[serde(default)]
+    pub is_writable: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct RampTxConfig {
+    pub is_onramp: bool,
+    /// Base58 user
+    pub user: String,
+    pub amount: u64,
+    /// Base58 L1 mint of a token ramp, native SOL if omitted
+    pub mint: Option<String>,
+    /// Decimals of `mint`, 9 if omitted
+    #[serde(default = "native_decimals")]
+    pub decimals: u8,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct L1MessageConfig {
+    /// Base58 L1 sender
+    pub sender: String,
+    /// Base58 program id
+    pub program_id: String,
+    #[serde(default)]
+    pub accounts: Vec<AccountMetaConfig>,
+    /// Base64 encoded instruction data
+    pub data: Option<String>,
+}
+
+fn native_decimals() -> u8 {
+    9
+}
+
+impl InputConfig {
+    pub fn from_json_str(s: &str) -> Result<Self, GenesisError> {
+        serde_json::from_str(s).map_err(|e| GenesisError::Parse(e.to_string()))
+    }
+
+    /// Builds the input, resolving relative paths against `base_dir`.
+    pub fn build(&self, base_dir: &Path) -> Result<ExecutionInput, GenesisError> {
+        let txs = self
+            .txs
+            .iter()
+            .map(|tx| build_transaction(base_dir, tx))
+            .collect::<Result<_, _>>()?;
+
+        let ramp_txs = self
+            .ramp_txs
+            .iter()
+            .map(|ramp_tx| {
+                Ok(RampTx {
+                    is_onramp: ramp_tx.is_onramp,
+                    user: parse_pubkey(&ramp_tx.user)?,
+                    amount: ramp_tx.amount,
+                    mint: ramp_tx.mint.as_deref().map(parse_pubkey).transpose()?,
+                    decimals: ramp_tx.decimals,
+                })
+            })
+            .collect::<Result<_, GenesisError>>()?;
+
+        let l1_messages = self
+            .l1_messages
+            .iter()
+            .map(|message| {
+                Ok(L1Message {
+                    sender: parse_pubkey(&message.sender)?,
+                    program_id: parse_pubkey(&message.program_id)?,
+                    accounts: build_account_metas(base_dir, &message.accounts)?,
+                    data: decode_data(&message.data)?,
+                })
+            })
+            .collect::<Result<_, GenesisError>>()?;
+
+        Ok(ExecutionInput {
+            batch_number: self.batch_number,
+            accounts: self.state.build(base_dir)?,
+            txs,
+            ramp_txs_start_hash: parse_hash(&self.ramp_txs_start_hash)?,
+            ramp_txs,
+            l1_messages_start_hash: parse_hash(&self.l1_messages_start_hash)?,
+            l1_messages,
+        })
+    }
+}
+
+/// Reads an input file, JSON if its extension is `.json` and bincode otherwise.
+pub fn load_input(path: &Path) -> Result<ExecutionInput, GenesisError> {
+    if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+        let bytes = fs::read(path).map_err(|e| GenesisError::Io(path.to_path_buf(), e))?;
+        return bincode::deserialize(&bytes).map_err(|e| GenesisError::Parse(e.to_string()));
+    }
+
+    let contents = fs::read_to_string(path).map_err(|e| GenesisError::Io(path.to_path_buf(), e))?;
+    let config = InputConfig::from_json_str(&contents)?;
+    config.build(path.parent().unwrap_or(Path::new(".")))
+}
+
+fn build_transaction(
+    base_dir: &Path,
+    config: &TransactionConfig,
+) -> Result<Transaction, GenesisError> {
+    let (instructions, signers, recent_blockhash) = match config {
+        TransactionConfig::Signed { base64 } => {
+            let bytes = BASE64
+                .decode(base64)
+                .map_err(|e| GenesisError::InvalidTransaction(e.to_string()))?;
+            return bincode::deserialize(&bytes)
+                .map_err(|e| GenesisError::InvalidTransaction(e.to_string()));
+        }
+        TransactionConfig::Unsigned {
+            instructions,
+            signers,
+            recent_blockhash,
+        } => (instructions, signers, recent_blockhash),
+    };
+
+    let instructions = instructions
+        .iter()
+        .map(|instruction| {
+            Ok(Instruction {
+                program_id: parse_pubkey(&instruction.program_id)?,
+                accounts: build_account_metas(base_dir, &instruction.accounts)?,
+                data: decode_data(&instruction.data)?,
+            })
+        })
+        .collect::<Result<Vec<_>, GenesisError>>()?;
+    let signers = signers
+        .iter()
+        .map(|path| read_keypair(&base_dir.join(path)))
+        .collect::<Result<Vec<Keypair>, _>>()?;
+    let payer = signers.first().ok_or_else(|| {
+        GenesisError::InvalidTransaction("a transaction has no signers".to_string())
+    })?;
+
+    let message = Message::new(&instructions, Some(&payer.pubkey()));
+    let mut transaction = Transaction::new_unsigned(message);
+    let signers: Vec<&Keypair> = signers.iter().collect();
+    transaction
+        .try_sign(&signers[..], parse_hash(recent_blockhash)?)
+        .map_err(|e| GenesisError::InvalidTransaction(e.to_string()))?;
+    Ok(transaction)
+}
+
+fn build_account_metas(
+    base_dir: &Path,
+    accounts: &[AccountMetaConfig],
+) -> Result<Vec<AccountMeta>, GenesisError> {
+    accounts
+        .iter()
+        .map(|account| {
+            Ok(AccountMeta {
+                pubkey: resolve_address(base_dir, &account.pubkey, &account.keypair)?,
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+        })
+        .collect()
+}
+
+fn decode_data(data: &Option<String>) -> Result<Vec<u8>, GenesisError> {
+    match data {
+        Some(data) => BASE64
+            .decode(data)
+            .map_err(|e| GenesisError::InvalidData(e.to_string())),
+        None => Ok(vec![]),
+    }
+}
+
+fn parse_hash(hash: &Option<String>) -> Result<Hash, GenesisError> {
+    match hash {
+        Some(hash) => Hash::from_str(hash).map_err(|_| GenesisError::InvalidHash(hash.clone())),
+        None => Ok(Hash::default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rollup_test_utils::temp_path;
+    use solana_sdk::{pubkey::Pubkey, system_instruction};
+
+    use super::*;
+
+    #[test]
+    fn test_build_from_json() {
+        let dir = temp_path("rollup-input");
+        fs::create_dir_all(&dir).unwrap();
+        let payer = Keypair::new();
+        fs::write(
+            dir.join("payer.json"),
+            serde_json::to_string(&payer.to_bytes().to_vec()).unwrap(),
+        )
+        .unwrap();
+        let recipient = Pubkey::new_unique();
+
+        let transfer = system_instruction::transfer(&payer.pubkey(), &recipient, 1000);
+        let blockhash = Hash::new_unique();
+        let signed = Transaction::new_signed_with_payer(
+            &[transfer.clone()],
+            Some(&payer.pubkey()),
+            &[&payer],
+            blockhash,
+        );
+        let path = dir.join("input.json");
+        fs::write(
+            &path,
+            format!(
+                r#"{{
+                    "batch_number": 3,
+                    "state": {{ "accounts": [{{ "keypair": "payer.json", "lamports": 5000 }}] }},
+                    "txs": [
+                        {{ "base64": "{}" }},
+                        {{
+                            "instructions": [{{
+                                "program_id": "{}",
+                                "accounts": [
+                                    {{ "keypair": "payer.json", "is_signer": true, "is_writable": true }},
+                                    {{ "pubkey": "{}", "is_writable": true }}
+                                ],
+                                "data": "{}"
+                            }}],
+                            "signers": ["payer.json"],
+                            "recent_blockhash": "{}"
+                        }}
+                    ],
+                    "ramp_txs": [{{ "is_onramp": true, "user": "{}", "amount": 10 }}]
+                }}"#,
+                BASE64.encode(bincode::serialize(&signed).unwrap()),
+                transfer.program_id,
+                recipient,
+                BASE64.encode(&transfer.data),
+                blockhash,
+                recipient,
+            ),
+        )
+        .unwrap();
+
+        let input = load_input(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(input.batch_number, 3);
+        assert_eq!(input.accounts.0[0].0, payer.pubkey());
+        assert_eq!(input.txs.len(), 2);
+        // Both forms describe the same transaction
+        assert_eq!(input.txs[0], input.txs[1]);
+        assert_eq!(input.ramp_txs[0].user, recipient);
+        assert_eq!(input.ramp_txs[0].decimals, 9);
+    }
+
+    #[test]
+    fn test_missing_signer() {
+        let config = InputConfig::from_json_str(
+            r#"{ "txs": [{
+                "instructions": [{ "program_id": "11111111111111111111111111111111" }],
+                "signers": []
+            }] }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.build(Path::new(".")),
+            Err(GenesisError::InvalidTransaction(_))
+        ));
+    }
+}
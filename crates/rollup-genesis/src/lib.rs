@@ -0,0 +1,307 @@
+//! Declarative genesis for the rollup.
+//!
+//! A genesis file (TOML or JSON) lists the accounts and programs present in the
+//! initial [`RollupState`]. Relative paths in the file are resolved against the
+//! directory containing it.
+//!
+//! ```toml
+//! spl_programs = false
+//!
+//! [[accounts]]
+//! keypair = "../onchain/tests/keypairSender.json"
+//! lamports = 0
+//!
+//! [[programs]]
+//! program_id = "RpXAja7ZvyqmCqS2k13hydLoumcZ76Mk4tHVAnhfDHD"
+//! elf = "../counter-program/counter_program.so"
+//! loader = "v4"
+//! ```
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Deserialize;
+use solana_sdk::{
+    account::Account, hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer,
+    system_program,
+};
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use svm_runner::{
+    programs::{program_account_v2, program_account_v4, program_accounts_v3},
+    spl::spl_program_accounts,
+};
+use svm_runner_types::{hash_state, RollupState};
+
+pub mod input;
+
+#[derive(Debug)]
+pub enum GenesisError {
+    Io(PathBuf, std::io::Error),
+    Parse(String),
+    UnsupportedFormat(PathBuf),
+    InvalidPubkey(String),
+    InvalidKeypair(PathBuf),
+    InvalidData(String),
+    InvalidHash(String),
+    InvalidTransaction(String),
+    MissingAddress,
+    AmbiguousAddress,
+    DuplicateAccount(Pubkey),
+}
+
+impl fmt::Display for GenesisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenesisError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            GenesisError::Parse(err) => write!(f, "invalid file: {}", err),
+            GenesisError::UnsupportedFormat(path) => {
+                write!(f, "{}: expected a .toml or .json file", path.display())
+            }
+            GenesisError::InvalidPubkey(pubkey) => write!(f, "invalid pubkey: {}", pubkey),
+            GenesisError::InvalidKeypair(path) => {
+                write!(f, "{}: invalid keypair file", path.display())
+            }
+            GenesisError::InvalidData(err) => write!(f, "invalid data: {}", err),
+            GenesisError::InvalidHash(hash) => write!(f, "invalid hash: {}", hash),
+            GenesisError::InvalidTransaction(err) => write!(f, "invalid transaction: {}", err),
+            GenesisError::MissingAddress => {
+                write!(f, "an entry has neither a pubkey nor a keypair")
+            }
+            GenesisError::AmbiguousAddress => {
+                write!(f, "an entry has both a pubkey and a keypair")
+            }
+            GenesisError::DuplicateAccount(pubkey) => {
+                write!(f, "account {} is defined more than once", pubkey)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenesisError {}
+
+/// Loader a genesis program is deployed with.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Loader {
+    /// `BPFLoader2111111111111111111111111111111111`
+    V2,
+    /// `BPFLoaderUpgradeab1e11111111111111111111111`
+    V3,
+    /// `LoaderV411111111111111111111111111111111111`
+    #[default]
+    V4,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisConfig {
+    #[serde(default)]
+    pub accounts: Vec<AccountConfig>,
+    #[serde(default)]
+    pub programs: Vec<ProgramConfig>,
+    /// Deploy SPL Token, Token-2022 and the Associated Token Account program in
+    /// the state instead of relying on the copies shipped with the runner
+    #[serde(default)]
+    pub spl_programs: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AccountConfig {
+    /// Base58 address of the account
+    pub pubkey: Option<String>,
+    /// Keypair file (JSON byte array) the address is taken from
+    pub keypair: Option<PathBuf>,
+    #[serde(default)]
+    pub lamports: u64,
+    /// Base58 owner, defaults to the system program
+    pub owner: Option<String>,
+    /// Base64 encoded account data
+    pub data: Option<String>,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProgramConfig {
+    /// Base58 program id
+    pub program_id: Option<String>,
+    /// Keypair file (JSON byte array) the program id is taken from
+    pub keypair: Option<PathBuf>,
+    /// Path to the program ELF
+    pub elf: PathBuf,
+    #[serde(default)]
+    pub loader: Loader,
+    /// Base58 authority of v3 and v4 programs, none (v3) or the default pubkey (v4) if omitted
+    pub authority: Option<String>,
+}
+
+/// The initial rollup state together with its root, as expected by `create_platform`.
+#[derive(Debug)]
+pub struct Genesis {
+    pub state: RollupState,
+    pub root: Hash,
+}
+
+impl GenesisConfig {
+    pub fn from_toml_str(s: &str) -> Result<Self, GenesisError> {
+        toml::from_str(s).map_err(|e| GenesisError::Parse(e.to_string()))
+    }
+
+    pub fn from_json_str(s: &str) -> Result<Self, GenesisError> {
+        serde_json::from_str(s).map_err(|e| GenesisError::Parse(e.to_string()))
+    }
+
+    /// Reads a genesis file, picking the format from its extension.
+    pub fn load(path: &Path) -> Result<Self, GenesisError> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| GenesisError::Io(path.to_path_buf(), e))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&contents),
+            Some("json") => Self::from_json_str(&contents),
+            _ => Err(GenesisError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Builds the state, resolving relative paths against `base_dir`.
+    ///
+    /// Accounts keep the order of the file, followed by programs and the SPL programs.
+    pub fn build(&self, base_dir: &Path) -> Result<RollupState, GenesisError> {
+        let mut accounts = Vec::new();
+
+        for config in &self.accounts {
+            let pubkey = resolve_address(base_dir, &config.pubkey, &config.keypair)?;
+            let owner = match &config.owner {
+                Some(owner) => parse_pubkey(owner)?,
+                None => system_program::id(),
+            };
+            let data = match &config.data {
+                Some(data) => BASE64
+                    .decode(data)
+                    .map_err(|e| GenesisError::InvalidData(e.to_string()))?,
+                None => vec![],
+            };
+            let account = Account {
+                lamports: config.lamports,
+                data,
+                owner,
+                executable: config.executable,
+                rent_epoch: 0,
+            };
+            accounts.push((pubkey, account.into()));
+        }
+
+        for config in &self.programs {
+            let program_id = resolve_address(base_dir, &config.program_id, &config.keypair)?;
+            let authority = config.authority.as_deref().map(parse_pubkey).transpose()?;
+            let elf_path = base_dir.join(&config.elf);
+            let elf = fs::read(&elf_path).map_err(|e| GenesisError::Io(elf_path, e))?;
+
+            match config.loader {
+                Loader::V2 => accounts.push((program_id, program_account_v2(&elf))),
+                Loader::V3 => accounts.extend(program_accounts_v3(&program_id, &elf, authority)),
+                Loader::V4 => accounts.push((
+                    program_id,
+                    program_account_v4(&elf, authority.unwrap_or_default()),
+                )),
+            }
+        }
+
+        if self.spl_programs {
+            accounts.extend(spl_program_accounts());
+        }
+
+        let mut seen = HashSet::new();
+        for (pubkey, _) in &accounts {
+            if !seen.insert(*pubkey) {
+                return Err(GenesisError::DuplicateAccount(*pubkey));
+            }
+        }
+
+        Ok(RollupState(accounts))
+    }
+}
+
+/// Loads a genesis file and computes the initial state root.
+pub fn load_genesis(path: &Path) -> Result<Genesis, GenesisError> {
+    let config = GenesisConfig::load(path)?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let state = config.build(base_dir)?;
+    let root = hash_state(&state);
+
+    Ok(Genesis { state, root })
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey, GenesisError> {
+    Pubkey::from_str(s).map_err(|_| GenesisError::InvalidPubkey(s.to_string()))
+}
+
+fn read_keypair(path: &Path) -> Result<Keypair, GenesisError> {
+    let bytes = fs::read(path).map_err(|e| GenesisError::Io(path.to_path_buf(), e))?;
+    let bytes: Vec<u8> = serde_json::from_slice(&bytes)
+        .map_err(|_| GenesisError::InvalidKeypair(path.to_path_buf()))?;
+    Keypair::from_bytes(&bytes).map_err(|_| GenesisError::InvalidKeypair(path.to_path_buf()))
+}
+
+fn resolve_address(
+    base_dir: &Path,
+    pubkey: &Option<String>,
+    keypair: &Option<PathBuf>,
+) -> Result<Pubkey, GenesisError> {
+    match (pubkey, keypair) {
+        (Some(pubkey), None) => parse_pubkey(pubkey),
+        (None, Some(keypair)) => Ok(read_keypair(&base_dir.join(keypair))?.pubkey()),
+        (None, None) => Err(GenesisError::MissingAddress),
+        (Some(_), Some(_)) => Err(GenesisError::AmbiguousAddress),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::account::ReadableAccount;
+
+    use super::*;
+
+    #[test]
+    fn test_build_from_toml() {
+        let config = GenesisConfig::from_toml_str(
+            r#"
+            [[accounts]]
+            pubkey = "BV97kHxvg2QpVgkuiWBJ2rESdJd3Gj8uK8aJ9kEB9oPd"
+            lamports = 1000
+
+            [[accounts]]
+            pubkey = "Gpi483RBiJ6UsPzkb1urzTyNc9Ceb68MAGuhY6RaQoum"
+            owner = "RpXAja7ZvyqmCqS2k13hydLoumcZ76Mk4tHVAnhfDHD"
+            data = "AAAAAA=="
+            "#,
+        )
+        .unwrap();
+        let state = config.build(Path::new(".")).unwrap();
+
+        assert_eq!(state.0.len(), 2);
+        assert_eq!(state.0[0].1.lamports(), 1000);
+        assert_eq!(state.0[0].1.owner(), &system_program::id());
+        assert_eq!(state.0[1].1.data(), &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_duplicate_account() {
+        let config = GenesisConfig::from_json_str(
+            r#"{ "accounts": [
+                { "pubkey": "BV97kHxvg2QpVgkuiWBJ2rESdJd3Gj8uK8aJ9kEB9oPd" },
+                { "pubkey": "BV97kHxvg2QpVgkuiWBJ2rESdJd3Gj8uK8aJ9kEB9oPd" }
+            ] }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            config.build(Path::new(".")),
+            Err(GenesisError::DuplicateAccount(_))
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+//! Helpers shared by the on-disk stores of the sequencer and the prover.
+
+use std::{fs, io, path::Path};
+
+use redb::{Database, TableError, WriteTransaction};
+
+pub use redb;
+
+/// Replaces `path` with `data`, so readers never see a partial file.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, data)?;
+    fs::File::open(&tmp)?.sync_all()?;
+    fs::rename(tmp, path)
+}
+
+/// Opens `path`, creating it and the tables `open_tables` opens if needed.
+pub fn create_database(
+    path: &Path,
+    open_tables: impl FnOnce(&WriteTransaction) -> Result<(), TableError>,
+) -> Result<Database, redb::Error> {
+    let db = Database::create(path)?;
+    // Opening a table never written to fails in a read transaction
+    let txn = db.begin_write()?;
+    open_tables(&txn)?;
+    txn.commit()?;
+    Ok(db)
+}
+
+/// Implements `From` the redb errors for an error enum with a
+/// `Database(redb::Error)` variant, so `?` works on database calls.
+#[macro_export]
+macro_rules! impl_from_redb_error {
+    ($target:ident) => {
+        $crate::impl_from_redb_error!(
+            $target,
+            $crate::redb::Error,
+            $crate::redb::DatabaseError,
+            $crate::redb::TransactionError,
+            $crate::redb::TableError,
+            $crate::redb::StorageError,
+            $crate::redb::CommitError
+        );
+    };
+    ($target:ident, $($error:ty),*) => {
+        $(impl From<$error> for $target {
+            fn from(err: $error) -> Self {
+                $target::Database(err.into())
+            }
+        })*
+    };
+}
@@ -0,0 +1,146 @@
+//! Fixtures shared by the tests, the script and the benchmarks.
+//!
+//! Programs are embedded at compile time, so nothing depends on the working
+//! directory.
+
+use solana_sdk::{
+    account::AccountSharedData,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction, system_program,
+    transaction::Transaction,
+};
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use svm_runner_types::{ExecutionInput, RampTx, RollupState};
+
+pub use svm_runner::programs::{program_account_v2, program_account_v4, program_accounts_v3};
+
+/// Increments the little-endian `u32` stored in its only account
+pub const COUNTER_PROGRAM_ELF: &[u8] =
+    include_bytes!("../../../counter-program/counter_program.so");
+
+/// Blockhash of the fixture transactions, the runner does not check it
+pub const BLOCKHASH: Hash = Hash::new_from_array([7; 32]);
+
+/// A path in the temp directory no other call returns, from this process or
+/// another one, to put the files of a test under.
+pub fn temp_path(name: &str) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("{}-{}-{}", name, std::process::id(), count))
+}
+
+/// A system account holding `lamports`.
+pub fn funded_account(lamports: u64) -> AccountSharedData {
+    AccountSharedData::new(lamports, 0, &system_program::id())
+}
+
+/// A counter at zero, owned by the counter program `program_id`.
+pub fn counter_account(program_id: &Pubkey) -> AccountSharedData {
+    AccountSharedData::new(100_000, 4, program_id)
+}
+
+/// Increments `counter`.
+pub fn increment(program_id: &Pubkey, counter: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![AccountMeta::new(*counter, false)],
+        data: vec![],
+    }
+}
+
+/// Signs `instructions` with [`BLOCKHASH`], the first signer paying the fees.
+pub fn signed_transaction(instructions: &[Instruction], signers: &[&Keypair]) -> Transaction {
+    Transaction::new_signed_with_payer(instructions, Some(&signers[0].pubkey()), signers, BLOCKHASH)
+}
+
+/// Native SOL deposited on L2.
+pub fn onramp(user: &Pubkey, amount: u64) -> RampTx {
+    RampTx {
+        is_onramp: true,
+        user: *user,
+        amount,
+        mint: None,
+        decimals: 9,
+    }
+}
+
+/// Native SOL withdrawn to L1.
+pub fn offramp(user: &Pubkey, amount: u64) -> RampTx {
+    RampTx {
+        is_onramp: false,
+        ..onramp(user, amount)
+    }
+}
+
+/// Onramps of `amount` for each of `users`, in order.
+pub fn onramps(users: &[Pubkey], amount: u64) -> Vec<RampTx> {
+    users.iter().map(|user| onramp(user, amount)).collect()
+}
+
+/// A batch funding `sender` with an onramp of 10 SOL, then transferring 1 SOL
+/// to `receiver` and incrementing a counter.
+///
+/// The accounts are, in order, the sender, the receiver, the counter program
+/// and the counter.
+pub fn counter_batch(sender: &Keypair, receiver: &Pubkey) -> ExecutionInput {
+    let counter_program_id = Pubkey::new_unique();
+    let counter = Pubkey::new_unique();
+    let accounts = RollupState(vec![
+        (sender.pubkey(), funded_account(0)),
+        (*receiver, funded_account(0)),
+        (
+            counter_program_id,
+            program_account_v4(COUNTER_PROGRAM_ELF, Pubkey::new_unique()),
+        ),
+        (counter, counter_account(&counter_program_id)),
+    ]);
+
+    counter_batch_on(
+        accounts,
+        sender,
+        receiver,
+        &counter_program_id,
+        &counter,
+        10 * LAMPORTS_PER_SOL,
+    )
+}
+
+/// The batch of [`counter_batch`] on `accounts`, which already hold the
+/// counter program and `counter`, with an onramp of `amount` of which a tenth
+/// is transferred.
+pub fn counter_batch_on(
+    accounts: RollupState,
+    sender: &Keypair,
+    receiver: &Pubkey,
+    counter_program_id: &Pubkey,
+    counter: &Pubkey,
+    amount: u64,
+) -> ExecutionInput {
+    ExecutionInput {
+        batch_number: 0,
+        accounts,
+        txs: vec![
+            signed_transaction(
+                &[system_instruction::transfer(
+                    &sender.pubkey(),
+                    receiver,
+                    amount / 10,
+                )],
+                &[sender],
+            ),
+            signed_transaction(&[increment(counter_program_id, counter)], &[sender]),
+        ],
+        ramp_txs_start_hash: Hash::default(),
+        ramp_txs: vec![onramp(&sender.pubkey(), amount)],
+        l1_messages_start_hash: Hash::default(),
+        l1_messages: vec![],
+    }
+}
@@ -0,0 +1,28 @@
+//! Cycle-tracker regions of the guest.
+//!
+//! In the zkVM, [`track`] prints the markers SP1 sums, by region name, into the
+//! `cycle_tracker` of the execution report. Elsewhere it only runs the closure.
+
+/// Application of the ramp txs of the batch
+pub const RAMP_TXS: &str = "ramp_txs";
+/// Execution of the L1 messages of the batch
+pub const L1_MESSAGES: &str = "l1_messages";
+/// Sanitization of the transactions of the batch
+pub const SANITIZE: &str = "sanitize";
+/// [`hash_state`](crate::hash_state), of the input and output states
+pub const HASH_STATE: &str = "hash_state";
+
+/// Region of the execution of the transaction at `index` in the batch
+pub fn transaction_region(index: usize) -> String {
+    format!("tx-{}", index)
+}
+
+/// Runs `f` in the region `name`.
+pub fn track<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    #[cfg(target_os = "zkvm")]
+    println!("cycle-tracker-report-start: {}", name);
+    let result = f();
+    #[cfg(target_os = "zkvm")]
+    println!("cycle-tracker-report-end: {}", name);
+    result
+}
@@ -1,85 +1,294 @@
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
-    account::{Account, AccountSharedData},
+    account::AccountSharedData,
     hash::{hashv, Hash},
+    instruction::AccountMeta,
     pubkey::Pubkey,
-    transaction::Transaction,
+    signature::Signature,
+    transaction::{Transaction, TransactionError},
 };
 
-#[derive(Deserialize, Serialize, Debug)]
+pub mod cycles;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct RampTx {
     pub is_onramp: bool,
     pub user: Pubkey,
     pub amount: u64,
+    /// L1 mint of a token ramp, `None` for native SOL
+    pub mint: Option<Pubkey>,
+    /// Decimals of `mint`, 9 for native SOL
+    pub decimals: u8,
+}
+
+/// An instruction queued on L1 through zk-bridge `send_message`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct L1Message {
+    /// L1 signer of `send_message`, signs on L2 through its alias
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+/// An instruction written to the L2 outbox, executable on L1 once its batch is proven
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct L2Message {
+    /// L2 signer of the outbox instruction, signs on L1 through its alias
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+/// Funds released on L1 by an offramp, or refunded for a deposit that could not be credited
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Withdrawal {
+    pub user: Pubkey,
+    pub amount: u64,
+    /// L1 mint of the tokens, `None` for native SOL
+    pub mint: Option<Pubkey>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ExecutionInput {
+    /// Number of the batch, the count of batches proven before it
+    pub batch_number: u64,
     pub accounts: RollupState,
     pub txs: Vec<Transaction>,
+    /// Ramp txs hash of the queue before `ramp_txs`
+    pub ramp_txs_start_hash: Hash,
     pub ramp_txs: Vec<RampTx>,
+    /// Message queue hash before `l1_messages`
+    pub l1_messages_start_hash: Hash,
+    pub l1_messages: Vec<L1Message>,
 }
 
-pub type ExecutionOutput = Hash;
+/// A batch for the prover, as the sequencer commits it
+#[derive(Deserialize, Serialize, Debug)]
+pub struct BatchJob {
+    /// State hash the sequencer committed before the batch, which the input
+    /// accounts must hash to
+    pub pre_state_hash: Hash,
+    pub input: ExecutionInput,
+}
 
+/// What executing a batch produces
 #[derive(Deserialize, Serialize, Debug)]
+pub struct RunnerOutput {
+    /// `svm_runner::config_hash` of the runner that executed the batch
+    pub config_hash: Hash,
+    pub state: RollupState,
+    /// Messages written to the outbox, in order
+    pub l2_messages: Vec<L2Message>,
+    /// Withdrawals released by the ramp txs, in order
+    pub withdrawals: Vec<Withdrawal>,
+    /// Indices of the ramp txs that could not be applied, such as an offramp
+    /// larger than the balance
+    pub skipped_ramp_txs: Vec<usize>,
+    /// Indices of the L1 messages that were rejected or failed, and had no effect
+    pub skipped_l1_messages: Vec<usize>,
+    /// One per transaction of the batch, in order
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// Outcome of a transaction of a batch
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct TransactionReceipt {
+    pub signature: Signature,
+    /// A failed transaction only paid its fee, unless it could not be loaded
+    /// at all and was dropped
+    pub status: Result<(), TransactionError>,
+    pub fee: u64,
+    pub compute_units_consumed: u64,
+    pub logs: Vec<String>,
+}
+
+impl TransactionReceipt {
+    /// Receipt of a transaction that could not be loaded and had no effect
+    pub fn dropped(signature: Signature, err: TransactionError) -> Self {
+        TransactionReceipt {
+            signature,
+            status: Err(err),
+            fee: 0,
+            compute_units_consumed: 0,
+            logs: Vec::new(),
+        }
+    }
+
+    /// Whether the transaction was dropped, which an executed one never is as
+    /// it always pays a fee
+    pub fn was_dropped(&self) -> bool {
+        self.status.is_err() && self.fee == 0
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct RollupState(pub Vec<(Pubkey, AccountSharedData)>);
 
 // Temporary function used before adding the merklized state
-pub fn hash_state(output: RollupState) -> Hash {
-    let mut data = Vec::new();
-    for (pk, account) in output.0.iter() {
-        data.extend_from_slice(pk.as_ref());
-        data.extend_from_slice(&bincode::serialize(account).unwrap());
-    }
-    hashv(&[data.as_slice()])
-}
-
-impl Into<onchain_types::RollupState> for RollupState {
-    fn into(self) -> onchain_types::RollupState {
-        let data = self
-            .0
-            .into_iter()
-            .map(|(pk, account)| {
-                let Account {
-                    lamports,
-                    data,
-                    owner,
-                    executable,
-                    rent_epoch,
-                } = account.into();
-                (
-                    onchain_types::Pubkey(pk.to_bytes()),
-                    onchain_types::Account {
-                        lamports,
-                        data,
-                        owner: onchain_types::Pubkey(owner.to_bytes()),
-                        executable,
-                        rent_epoch,
-                    },
-                )
-            })
-            .collect();
-        onchain_types::RollupState(data)
+pub fn hash_state(output: &RollupState) -> Hash {
+    cycles::track(cycles::HASH_STATE, || {
+        let mut data = Vec::new();
+        for (pk, account) in output.0.iter() {
+            data.extend_from_slice(pk.as_ref());
+            data.extend_from_slice(&bincode::serialize(account).unwrap());
+        }
+        hashv(&[data.as_slice()])
+    })
+}
+
+/// Hash of the transactions of a batch
+pub fn hash_txs(txs: &[Transaction]) -> Hash {
+    hashv(&[&bincode::serialize(txs).unwrap()])
+}
+
+/// Values the guest commits for `input` executed into `output`
+pub fn public_values(input: &ExecutionInput, output: &RunnerOutput) -> onchain_types::PublicValues {
+    onchain_types::PublicValues {
+        pre_state_hash: hash_state(&input.accounts).to_bytes(),
+        post_state_hash: hash_state(&output.state).to_bytes(),
+        ramp_txs_start_hash: input.ramp_txs_start_hash.to_bytes(),
+        ramp_txs_hash: hash_ramp_txs(input.ramp_txs_start_hash, &input.ramp_txs).to_bytes(),
+        ramp_txs_count: input.ramp_txs.len() as u64,
+        l1_messages_start_hash: input.l1_messages_start_hash.to_bytes(),
+        l1_messages_hash: hash_l1_messages(input.l1_messages_start_hash, &input.l1_messages)
+            .to_bytes(),
+        l1_messages_count: input.l1_messages.len() as u64,
+        txs_hash: hash_txs(&input.txs).to_bytes(),
+        outbox_root: outbox_root(&output.l2_messages).to_bytes(),
+        withdrawals_root: withdrawals_root(&output.withdrawals).to_bytes(),
+        batch_number: input.batch_number,
+        config_hash: output.config_hash.to_bytes(),
     }
 }
 
+/// Ramp txs hash zk-bridge accumulates in `add_ramp_tx` and `add_token_ramp_tx`,
+/// continued from `start` over `ramp_txs`
+pub fn hash_ramp_txs(start: Hash, ramp_txs: &[RampTx]) -> Hash {
+    ramp_txs.iter().fold(start, |ramp_txs_hash, ramp_tx| {
+        let ramp_tx: onchain_types::RampTx = ramp_tx.clone().into();
+        hashv(&[ramp_txs_hash.as_ref(), &ramp_tx.to_bytes()])
+    })
+}
+
+/// Message queue hash zk-bridge accumulates in `send_message`, continued from
+/// `start` over `messages`
+pub fn hash_l1_messages(start: Hash, messages: &[L1Message]) -> Hash {
+    messages.iter().fold(start, |queue_hash, message| {
+        let message: onchain_types::L1Message = message.clone().into();
+        hashv(&[queue_hash.as_ref(), &message.to_bytes()])
+    })
+}
+
+/// Merkle root of the outbox zk-bridge checks L2 messages against
+pub fn outbox_root(messages: &[L2Message]) -> Hash {
+    Hash::new_from_array(onchain_types::merkle::root(
+        solana_hashv,
+        &outbox_leaves(messages),
+    ))
+}
+
+/// Proof of the message at `index` for zk-bridge `execute_l2_message`
+pub fn outbox_proof(messages: &[L2Message], index: usize) -> Vec<[u8; 32]> {
+    onchain_types::merkle::proof(solana_hashv, &outbox_leaves(messages), index)
+}
+
+/// Merkle root of the withdrawals zk-bridge pays out against
+pub fn withdrawals_root(withdrawals: &[Withdrawal]) -> Hash {
+    Hash::new_from_array(onchain_types::merkle::root(
+        solana_hashv,
+        &withdrawal_leaves(withdrawals),
+    ))
+}
+
+/// Proof of the withdrawal at `index` for zk-bridge `withdraw` and `withdraw_token`
+pub fn withdrawal_proof(withdrawals: &[Withdrawal], index: usize) -> Vec<[u8; 32]> {
+    onchain_types::merkle::proof(solana_hashv, &withdrawal_leaves(withdrawals), index)
+}
+
+fn withdrawal_leaves(withdrawals: &[Withdrawal]) -> Vec<[u8; 32]> {
+    withdrawals
+        .iter()
+        .enumerate()
+        .map(|(index, withdrawal)| {
+            let withdrawal: onchain_types::Withdrawal = withdrawal.clone().into();
+            withdrawal.leaf_hash(solana_hashv, index as u64)
+        })
+        .collect()
+}
+
+fn outbox_leaves(messages: &[L2Message]) -> Vec<[u8; 32]> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| {
+            let message: onchain_types::L2Message = message.clone().into();
+            message.leaf_hash(solana_hashv, index as u64)
+        })
+        .collect()
+}
+
+fn solana_hashv(vals: &[&[u8]]) -> [u8; 32] {
+    hashv(vals).to_bytes()
+}
+
 impl Into<onchain_types::RampTx> for RampTx {
     fn into(self) -> onchain_types::RampTx {
         onchain_types::RampTx {
             is_onramp: self.is_onramp,
             user: onchain_types::Pubkey(self.user.to_bytes()),
             amount: self.amount,
+            mint: self.mint.map(|mint| onchain_types::Pubkey(mint.to_bytes())),
+            decimals: self.decimals,
+        }
+    }
+}
+
+impl Into<onchain_types::L1Message> for L1Message {
+    fn into(self) -> onchain_types::L1Message {
+        onchain_types::L1Message {
+            sender: onchain_types::Pubkey(self.sender.to_bytes()),
+            program_id: onchain_types::Pubkey(self.program_id.to_bytes()),
+            accounts: self
+                .accounts
+                .into_iter()
+                .map(|meta| onchain_types::AccountMeta {
+                    pubkey: onchain_types::Pubkey(meta.pubkey.to_bytes()),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: self.data,
         }
     }
 }
 
-impl Into<onchain_types::ExecutionInput> for ExecutionInput {
-    fn into(self) -> onchain_types::ExecutionInput {
-        onchain_types::ExecutionInput {
-            accounts: self.accounts.into(),
-            txs: bincode::serialize(&self.txs).unwrap(),
-            ramp_txs: self.ramp_txs.into_iter().map(|r| r.into()).collect(),
+impl Into<onchain_types::L2Message> for L2Message {
+    fn into(self) -> onchain_types::L2Message {
+        onchain_types::L2Message {
+            sender: onchain_types::Pubkey(self.sender.to_bytes()),
+            program_id: onchain_types::Pubkey(self.program_id.to_bytes()),
+            accounts: self
+                .accounts
+                .into_iter()
+                .map(|meta| onchain_types::AccountMeta {
+                    pubkey: onchain_types::Pubkey(meta.pubkey.to_bytes()),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: self.data,
+        }
+    }
+}
+
+impl Into<onchain_types::Withdrawal> for Withdrawal {
+    fn into(self) -> onchain_types::Withdrawal {
+        onchain_types::Withdrawal {
+            user: onchain_types::Pubkey(self.user.to_bytes()),
+            amount: self.amount,
+            mint: self.mint.map(|mint| onchain_types::Pubkey(mint.to_bytes())),
         }
     }
 }
@@ -0,0 +1,197 @@
+//! Accounts the bridge maintains on L2 for ramp transactions and L1 messages.
+//!
+//! Every L1 mint is represented by a wrapped mint at a deterministic address,
+//! with the bridge as mint authority. Token ramps credit or debit the user's
+//! associated token account for the wrapped mint.
+//!
+//! L1 messages are executed as transactions without fees, paid by an account
+//! of the bridge holding no authority, and signed by the alias of their L1
+//! sender.
+
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount, WritableAccount},
+    instruction::Instruction,
+    message::Message,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Signature,
+    system_program,
+    transaction::{SanitizedTransaction, Transaction},
+};
+use spl_associated_token_account_client::address::get_associated_token_address;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use std::collections::{HashMap, HashSet};
+use svm_runner_types::{L1Message, RampTx};
+
+solana_sdk::declare_id!("ZkBridge11111111111111111111111111111111111");
+
+pub const WRAPPED_MINT_SEED: &[u8] = b"wrapped_mint";
+
+pub const L1_SENDER_SEED: &[u8] = b"l1_sender";
+
+pub const MESSAGE_FEE_PAYER_SEED: &[u8] = b"message_fee_payer";
+
+/// The L2 mint standing for `l1_mint`.
+pub fn wrapped_mint_address(l1_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[WRAPPED_MINT_SEED, l1_mint.as_ref()], &ID).0
+}
+
+/// The address signing on L2 for the L1 `sender` of a message.
+pub fn l1_sender_alias(sender: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[L1_SENDER_SEED, sender.as_ref()], &ID).0
+}
+
+/// The account paying for L1 message transactions.
+///
+/// It signs every message transaction, so it must not be an authority over
+/// anything. In particular it is not [`ID`], the authority of wrapped mints.
+pub fn message_fee_payer_address() -> Pubkey {
+    Pubkey::find_program_address(&[MESSAGE_FEE_PAYER_SEED], &ID).0
+}
+
+pub(crate) fn message_fee_payer_account() -> AccountSharedData {
+    AccountSharedData::new(Rent::default().minimum_balance(0), 0, &system_program::id())
+}
+
+/// The transaction executing `message`.
+///
+/// Returns `None` if the message asks for any signer other than the alias of
+/// its sender, or lists the bridge or the fee payer. Signers are merged by
+/// address, so listing the fee payer, even as a non-signer, would make the
+/// instruction signed by it.
+pub(crate) fn l1_message_transaction(message: &L1Message) -> Option<SanitizedTransaction> {
+    let alias = l1_sender_alias(&message.sender);
+    let fee_payer = message_fee_payer_address();
+    if message.program_id == ID
+        || message.program_id == fee_payer
+        || message.accounts.iter().any(|meta| {
+            (meta.is_signer && meta.pubkey != alias)
+                || meta.pubkey == ID
+                || meta.pubkey == fee_payer
+        })
+    {
+        return None;
+    }
+
+    let instruction = Instruction {
+        program_id: message.program_id,
+        accounts: message.accounts.clone(),
+        data: message.data.clone(),
+    };
+    let message = Message::new(&[instruction], Some(&fee_payer));
+    let transaction = Transaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message,
+    };
+
+    SanitizedTransaction::try_from_legacy_transaction(transaction, &HashSet::new()).ok()
+}
+
+/// Credits or debits the wrapped tokens of a token ramp transaction.
+///
+/// The rent of a wrapped mint or token account created here is paid from the
+/// user's lamports, so users need to onramp SOL before their first token
+/// deposit. Anyone can transfer lamports to their addresses beforehand, so, as
+/// in the associated token account program, a system account without data
+/// there is created over, keeping its lamports. Returns `None` if the ramp
+/// could not be applied, leaving the accounts untouched.
+pub(crate) fn apply_token_ramp_tx(
+    accounts: &mut HashMap<Pubkey, AccountSharedData>,
+    tx: &RampTx,
+    l1_mint: &Pubkey,
+) -> Option<()> {
+    let rent = Rent::default();
+    let mint_address = wrapped_mint_address(l1_mint);
+    let token_account_address = get_associated_token_address(&tx.user, &mint_address);
+    let mut rent_due = 0;
+
+    let (mut mint, mint_lamports) = match allocated_account(accounts, &mint_address) {
+        Some(account) => (Mint::unpack(account.data()).ok()?, account.lamports()),
+        None if tx.is_onramp => {
+            let funded = lamports_of(accounts, &mint_address);
+            let lamports = rent.minimum_balance(Mint::LEN).max(funded);
+            rent_due += lamports - funded;
+            let mint = Mint {
+                mint_authority: COption::Some(ID),
+                supply: 0,
+                decimals: tx.decimals,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            };
+            (mint, lamports)
+        }
+        None => return None,
+    };
+
+    let (mut token_account, token_account_lamports) =
+        match allocated_account(accounts, &token_account_address) {
+            Some(account) => (
+                TokenAccount::unpack(account.data()).ok()?,
+                account.lamports(),
+            ),
+            None if tx.is_onramp => {
+                let funded = lamports_of(accounts, &token_account_address);
+                let lamports = rent.minimum_balance(TokenAccount::LEN).max(funded);
+                rent_due += lamports - funded;
+                let token_account = TokenAccount {
+                    mint: mint_address,
+                    owner: tx.user,
+                    amount: 0,
+                    delegate: COption::None,
+                    state: AccountState::Initialized,
+                    is_native: COption::None,
+                    delegated_amount: 0,
+                    close_authority: COption::None,
+                };
+                (token_account, lamports)
+            }
+            None => return None,
+        };
+
+    if tx.is_onramp {
+        mint.supply = mint.supply.checked_add(tx.amount)?;
+        token_account.amount = token_account.amount.checked_add(tx.amount)?;
+    } else {
+        token_account.amount = token_account.amount.checked_sub(tx.amount)?;
+        mint.supply = mint.supply.checked_sub(tx.amount)?;
+    }
+
+    if rent_due > 0 {
+        let user = accounts.get(&tx.user)?;
+        let remaining = user.lamports().checked_sub(rent_due)?;
+        if remaining != 0 && remaining < rent.minimum_balance(user.data().len()) {
+            return None;
+        }
+        accounts.get_mut(&tx.user)?.set_lamports(remaining);
+    }
+
+    let mut mint_account = AccountSharedData::new(mint_lamports, Mint::LEN, &spl_token::id());
+    Mint::pack(mint, mint_account.data_as_mut_slice()).ok()?;
+    accounts.insert(mint_address, mint_account);
+
+    let mut account =
+        AccountSharedData::new(token_account_lamports, TokenAccount::LEN, &spl_token::id());
+    TokenAccount::pack(token_account, account.data_as_mut_slice()).ok()?;
+    accounts.insert(token_account_address, account);
+
+    Some(())
+}
+
+/// The account at `address`, unless it is missing or a system account without
+/// data, which anyone can create by transferring lamports to it.
+fn allocated_account<'a>(
+    accounts: &'a HashMap<Pubkey, AccountSharedData>,
+    address: &Pubkey,
+) -> Option<&'a AccountSharedData> {
+    accounts
+        .get(address)
+        .filter(|account| !(account.owner() == &system_program::id() && account.data().is_empty()))
+}
+
+fn lamports_of(accounts: &HashMap<Pubkey, AccountSharedData>, address: &Pubkey) -> u64 {
+    accounts
+        .get(address)
+        .map_or(0, |account| account.lamports())
+}
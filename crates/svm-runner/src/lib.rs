@@ -2,23 +2,31 @@ use solana_bpf_loader_program::syscalls::create_program_runtime_environment_v1;
 use solana_compute_budget::compute_budget::ComputeBudget;
 use solana_program_runtime::loaded_programs::{BlockRelation, ForkGraph, ProgramCacheEntry};
 use solana_sdk::{
-    account::{AccountSharedData, ReadableAccount, WritableAccount},
-    clock::Slot,
+    account::{
+        create_account_shared_data_with_fields, AccountSharedData, ReadableAccount, WritableAccount,
+    },
+    bpf_loader, bpf_loader_upgradeable,
+    clock::{Clock, Slot},
     feature_set::FeatureSet,
     fee::FeeStructure,
-    hash::Hash,
+    hash::{hashv, Hash},
     loader_v4, native_loader,
     pubkey::Pubkey,
+    rent::Rent,
     rent_collector::RentCollector,
+    system_program, sysvar,
     transaction::{self, SanitizedTransaction, TransactionError},
 };
 
 use solana_svm::{
     account_loader::CheckedTransactionDetails,
+    rollback_accounts::RollbackAccounts,
+    transaction_execution_result::ExecutedTransaction,
     transaction_processing_callback::TransactionProcessingCallback,
     transaction_processing_result::ProcessedTransaction,
     transaction_processor::{
-        TransactionBatchProcessor, TransactionProcessingConfig, TransactionProcessingEnvironment,
+        ExecutionRecordingConfig, TransactionBatchProcessor, TransactionProcessingConfig,
+        TransactionProcessingEnvironment,
     },
 };
 
@@ -27,7 +35,14 @@ use std::{
     collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
 };
-use svm_runner_types::{ExecutionInput, RollupState};
+use svm_runner_types::{
+    cycles, ExecutionInput, RollupState, RunnerOutput, TransactionReceipt, Withdrawal,
+};
+
+pub mod bridge;
+pub mod outbox;
+pub mod programs;
+pub mod spl;
 
 pub(crate) struct MockForkGraph {}
 
@@ -64,24 +79,76 @@ impl TransactionProcessingCallback for MockAccountLoader {
     }
 }
 
-pub fn runner(input: &ExecutionInput) -> Result<RollupState, TransactionError> {
+/// Version of the execution rules the rest of the config hash does not
+/// capture, such as the feature set and the compute budget of the Solana
+/// version the runner builds with
+const CONFIG_VERSION: &[u8] = b"zk-svm-runner-config-v1";
+
+/// Builtin programs the runner registers, and the bridge
+const BUILTIN_PROGRAM_IDS: [Pubkey; 6] = [
+    system_program::ID,
+    bpf_loader::ID,
+    bpf_loader_upgradeable::ID,
+    loader_v4::ID,
+    outbox::ID,
+    bridge::ID,
+];
+
+/// Hash of the configuration batches are executed with: the fees, the
+/// builtin programs and the SPL programs shipped with the runner.
+///
+/// Proofs commit to it and zk-bridge `prove` checks it against the platform,
+/// so a platform only accepts batches executed the way it was created for.
+pub fn config_hash() -> Hash {
+    let lamports_per_signature = FeeStructure::default().lamports_per_signature.to_le_bytes();
+    let mut config: Vec<&[u8]> = vec![CONFIG_VERSION, &lamports_per_signature];
+    config.extend(BUILTIN_PROGRAM_IDS.iter().map(|id| id.as_ref()));
+    for (program_id, loader_id, _, checksum) in spl::SPL_PROGRAMS {
+        config.extend([program_id.as_ref(), loader_id.as_ref(), checksum.as_bytes()]);
+    }
+    hashv(&config)
+}
+
+pub fn runner(input: &ExecutionInput) -> Result<RunnerOutput, TransactionError> {
     let mut account_shared_data = HashMap::<Pubkey, AccountSharedData>::new();
 
     for (pk, account) in &input.accounts.0 {
         account_shared_data.insert(*pk, account.clone());
     }
 
-    // Process ramp txs
-    for tx in &input.ramp_txs {
-        let account = account_shared_data.get_mut(&tx.user).unwrap();
-
-        if tx.is_onramp {
-            account.set_lamports(account.lamports() + tx.amount);
-        } else {
-            account.set_lamports(account.lamports() - tx.amount);
+    // Programs shipped with the runner, unless deployed in the state. A
+    // program can only be invoked, directly or through CPI, by a transaction
+    // listing it, so the others are not loaded.
+    let referenced_accounts: HashSet<&Pubkey> = input
+        .txs
+        .iter()
+        .flat_map(|tx| tx.message.account_keys.iter())
+        .chain(input.l1_messages.iter().flat_map(|message| {
+            std::iter::once(&message.program_id)
+                .chain(message.accounts.iter().map(|meta| &meta.pubkey))
+        }))
+        .collect();
+    for program in spl::spl_programs() {
+        if referenced_accounts.contains(&program.program_id) {
+            for (pk, account) in &program.accounts {
+                account_shared_data
+                    .entry(*pk)
+                    .or_insert_with(|| account.clone());
+            }
         }
     }
 
+    account_shared_data
+        .entry(sysvar::rent::id())
+        .or_insert_with(|| create_account_shared_data_with_fields(&Rent::default(), (1, 0)));
+    account_shared_data
+        .entry(sysvar::clock::id())
+        .or_insert_with(|| create_account_shared_data_with_fields(&Clock::default(), (1, 0)));
+    account_shared_data
+        .entry(bridge::message_fee_payer_address())
+        .or_insert_with(bridge::message_fee_payer_account);
+    account_shared_data.insert(outbox::outbox_address(), outbox::outbox_account());
+
     let account_loader = MockAccountLoader {
         account_shared_data: Arc::new(RwLock::new(account_shared_data)),
     };
@@ -115,27 +182,27 @@ pub fn runner(input: &ExecutionInput) -> Result<RollupState, TransactionError> {
         ),
     );
 
-    // processor.add_builtin(
-    //     &account_loader,
-    //     solana_sdk::bpf_loader::id(),
-    //     "solana_bpf_loader_program",
-    //     ProgramCacheEntry::new_builtin(
-    //         0,
-    //         b"solana_bpf_loader_program".len(),
-    //         solana_bpf_loader_program::Entrypoint::vm,
-    //     ),
-    // );
-
-    // processor.add_builtin(
-    //     &account_loader,
-    //     solana_sdk::bpf_loader::id(),
-    //     "solana_bpf_loader_upgradeable_program",
-    //     ProgramCacheEntry::new_builtin(
-    //         0,
-    //         b"solana_bpf_loader_upgradeable_program".len(),
-    //         solana_bpf_loader_program::Entrypoint::vm,
-    //     ),
-    // );
+    processor.add_builtin(
+        &account_loader,
+        bpf_loader::id(),
+        "solana_bpf_loader_program",
+        ProgramCacheEntry::new_builtin(
+            0,
+            b"solana_bpf_loader_program".len(),
+            solana_bpf_loader_program::Entrypoint::vm,
+        ),
+    );
+
+    processor.add_builtin(
+        &account_loader,
+        bpf_loader_upgradeable::id(),
+        "solana_bpf_loader_upgradeable_program",
+        ProgramCacheEntry::new_builtin(
+            0,
+            b"solana_bpf_loader_upgradeable_program".len(),
+            solana_bpf_loader_program::Entrypoint::vm,
+        ),
+    );
 
     processor.add_builtin(
         &account_loader,
@@ -148,13 +215,67 @@ pub fn runner(input: &ExecutionInput) -> Result<RollupState, TransactionError> {
         ),
     );
 
-    let mut svm_transactions: Vec<SanitizedTransaction> = Vec::new();
+    processor.add_builtin(
+        &account_loader,
+        outbox::ID,
+        "zk_outbox_program",
+        ProgramCacheEntry::new_builtin(0, b"zk_outbox_program".len(), outbox::Entrypoint::vm),
+    );
+
+    processor.fill_missing_sysvar_cache_entries(&account_loader);
+
+    // Anything not in this set when the batch ends was created by a transaction
+    let preloaded_accounts: HashSet<Pubkey> = account_loader
+        .account_shared_data
+        .read()
+        .unwrap()
+        .keys()
+        .copied()
+        .collect();
+
+    // Process ramp txs. Offramps release withdrawals claimed on L1, and so do
+    // token deposits that could not be credited, as a refund.
+    let (withdrawals, skipped_ramp_txs) = cycles::track(cycles::RAMP_TXS, || {
+        let mut withdrawals = Vec::new();
+        let mut skipped_ramp_txs = Vec::new();
+        let mut account_shared_data = account_loader.account_shared_data.write().unwrap();
+        for (index, tx) in input.ramp_txs.iter().enumerate() {
+            let withdrawal = Withdrawal {
+                user: tx.user,
+                amount: tx.amount,
+                mint: tx.mint,
+            };
+
+            if let Some(l1_mint) = &tx.mint {
+                let applied =
+                    bridge::apply_token_ramp_tx(&mut account_shared_data, tx, l1_mint).is_some();
+                if !applied {
+                    skipped_ramp_txs.push(index);
+                }
+                if applied != tx.is_onramp {
+                    withdrawals.push(withdrawal);
+                }
+                continue;
+            }
+
+            if tx.is_onramp {
+                let account = account_shared_data
+                    .entry(tx.user)
+                    .or_insert_with(|| AccountSharedData::new(0, 0, &system_program::id()));
+                account.set_lamports(account.lamports().saturating_add(tx.amount));
+                continue;
+            }
 
-    for tx in &input.txs {
-        let sanitized_transaction =
-            SanitizedTransaction::try_from_legacy_transaction(tx.clone(), &HashSet::new()).unwrap();
-        svm_transactions.push(sanitized_transaction);
-    }
+            match account_shared_data.get_mut(&tx.user) {
+                Some(account) if account.lamports() >= tx.amount => {
+                    account.set_lamports(account.lamports() - tx.amount);
+                    withdrawals.push(withdrawal);
+                }
+                _ => skipped_ramp_txs.push(index),
+            }
+        }
+        (withdrawals, skipped_ramp_txs)
+    });
 
     let fee_structure = FeeStructure::default();
     let rent_collector = RentCollector::default();
@@ -171,63 +292,185 @@ pub fn runner(input: &ExecutionInput) -> Result<RollupState, TransactionError> {
 
     let processing_config = TransactionProcessingConfig {
         compute_budget: Some(ComputeBudget::default()),
+        recording_config: ExecutionRecordingConfig {
+            enable_log_recording: true,
+            ..Default::default()
+        },
         ..Default::default()
     };
 
-    let results = processor.load_and_execute_sanitized_transactions(
-        &account_loader,
-        &svm_transactions,
-        get_transaction_check_results(svm_transactions.len(), fee_structure.lamports_per_signature),
-        &processing_environment,
-        &processing_config,
-    );
+    // L1 messages run first and without fees. A failing message is skipped
+    // rather than failing the batch, since anyone can queue one on L1.
+    let skipped_l1_messages = cycles::track(cycles::L1_MESSAGES, || {
+        let mut skipped_l1_messages = Vec::new();
+        let mut message_indices = Vec::new();
+        let mut message_transactions = Vec::new();
+        for (index, message) in input.l1_messages.iter().enumerate() {
+            match bridge::l1_message_transaction(message) {
+                Some(transaction) => {
+                    message_indices.push(index);
+                    message_transactions.push(transaction);
+                }
+                None => skipped_l1_messages.push(index),
+            }
+        }
 
-    for (tx_index, processed_transaction) in results.processing_results.iter().enumerate() {
-        let sanitized_transaction = &svm_transactions[tx_index];
+        let results = processor.load_and_execute_sanitized_transactions(
+            &account_loader,
+            &message_transactions,
+            get_transaction_check_results(message_transactions.len(), 0),
+            &processing_environment,
+            &processing_config,
+        );
 
-        match processed_transaction {
-            Ok(ProcessedTransaction::Executed(executed_transaction)) => {
-                for (index, (pubkey, account_data)) in executed_transaction
-                    .loaded_transaction
-                    .accounts
-                    .iter()
-                    .enumerate()
+        for (tx_index, processed_transaction) in results.processing_results.iter().enumerate() {
+            match processed_transaction {
+                Ok(ProcessedTransaction::Executed(executed_transaction))
+                    if executed_transaction.execution_details.status.is_ok() =>
                 {
-                    if sanitized_transaction.is_writable(index) {
-                        account_loader
-                            .account_shared_data
-                            .write()
-                            .unwrap()
-                            .insert(*pubkey, account_data.clone());
-                    }
+                    commit_transaction(
+                        &account_loader,
+                        &message_transactions[tx_index],
+                        executed_transaction,
+                    );
                 }
+                _ => skipped_l1_messages.push(message_indices[tx_index]),
             }
-            Ok(ProcessedTransaction::FeesOnly(fees_only_transaction)) => {
-                return Err(fees_only_transaction.load_error.clone())
-            }
-            Err(err) => return Err(err.clone()),
         }
-    }
+        skipped_l1_messages.sort_unstable();
+        skipped_l1_messages
+    });
 
-    Ok(RollupState(
+    let svm_transactions = cycles::track(cycles::SANITIZE, || {
         input
-            .accounts
-            .0
+            .txs
             .iter()
-            .map(|state| {
-                (
-                    state.0.clone(),
-                    account_loader
-                        .account_shared_data
-                        .read()
-                        .unwrap()
-                        .get(&state.0)
-                        .unwrap()
-                        .clone(),
-                )
+            .map(|tx| {
+                SanitizedTransaction::try_from_legacy_transaction(tx.clone(), &HashSet::new())
             })
-            .collect(),
-    ))
+            .collect::<Vec<_>>()
+    });
+
+    // Transactions are executed one at a time, committing each before the
+    // next, so the cycles of every transaction can be tracked. A transaction
+    // that is malformed or cannot be loaded is dropped without charging fees,
+    // so a batch stays valid whatever the sequencer put in it.
+    let mut receipts = Vec::with_capacity(svm_transactions.len());
+    for (tx_index, (tx, transaction)) in input.txs.iter().zip(&svm_transactions).enumerate() {
+        let transaction = match transaction {
+            Ok(transaction) => transaction,
+            Err(err) => {
+                let signature = tx.signatures.first().copied().unwrap_or_default();
+                receipts.push(TransactionReceipt::dropped(signature, err.clone()));
+                continue;
+            }
+        };
+        let receipt = cycles::track(&cycles::transaction_region(tx_index), || {
+            let results = processor.load_and_execute_sanitized_transactions(
+                &account_loader,
+                std::slice::from_ref(transaction),
+                get_transaction_check_results(1, fee_structure.lamports_per_signature),
+                &processing_environment,
+                &processing_config,
+            );
+
+            let signature = *transaction.signature();
+            match &results.processing_results[0] {
+                Ok(ProcessedTransaction::Executed(executed_transaction)) => {
+                    commit_transaction(&account_loader, transaction, executed_transaction);
+                    let details = &executed_transaction.execution_details;
+                    TransactionReceipt {
+                        signature,
+                        status: details.status.clone(),
+                        fee: executed_transaction
+                            .loaded_transaction
+                            .fee_details
+                            .total_fee(),
+                        compute_units_consumed: details.executed_units,
+                        logs: details.log_messages.clone().unwrap_or_default(),
+                    }
+                }
+                Ok(ProcessedTransaction::FeesOnly(fees_only_transaction)) => {
+                    TransactionReceipt::dropped(signature, fees_only_transaction.load_error.clone())
+                }
+                Err(err) => TransactionReceipt::dropped(signature, err.clone()),
+            }
+        });
+        receipts.push(receipt);
+    }
+
+    let account_shared_data = account_loader.account_shared_data.read().unwrap();
+
+    let mut output: Vec<(Pubkey, AccountSharedData)> = input
+        .accounts
+        .0
+        .iter()
+        .map(|(pk, _)| (*pk, account_shared_data.get(pk).unwrap().clone()))
+        .collect();
+
+    // New accounts follow the input ones, ordered by address
+    let mut created_accounts: Vec<(Pubkey, AccountSharedData)> = account_shared_data
+        .iter()
+        .filter(|(pk, account)| !preloaded_accounts.contains(pk) && account.lamports() > 0)
+        .map(|(pk, account)| (*pk, account.clone()))
+        .collect();
+    created_accounts.sort_by_key(|(pk, _)| *pk);
+    output.extend(created_accounts);
+
+    let l2_messages =
+        outbox::read_messages(account_shared_data.get(&outbox::outbox_address()).unwrap());
+
+    Ok(RunnerOutput {
+        config_hash: config_hash(),
+        state: RollupState(output),
+        l2_messages,
+        withdrawals,
+        skipped_ramp_txs,
+        skipped_l1_messages,
+        receipts,
+    })
+}
+
+/// Writes back the writable accounts of an executed transaction. A failed
+/// transaction only pays its fee, so only its fee payer and nonce account are
+/// written back, as they were before execution.
+fn commit_transaction(
+    account_loader: &MockAccountLoader,
+    sanitized_transaction: &SanitizedTransaction,
+    executed_transaction: &ExecutedTransaction,
+) {
+    let mut account_shared_data = account_loader.account_shared_data.write().unwrap();
+
+    if !executed_transaction.was_successful() {
+        let fee_payer = *sanitized_transaction.fee_payer();
+        match &executed_transaction.loaded_transaction.rollback_accounts {
+            RollbackAccounts::FeePayerOnly { fee_payer_account } => {
+                account_shared_data.insert(fee_payer, fee_payer_account.clone());
+            }
+            RollbackAccounts::SameNonceAndFeePayer { nonce } => {
+                account_shared_data.insert(*nonce.address(), nonce.account().clone());
+            }
+            RollbackAccounts::SeparateNonceAndFeePayer {
+                nonce,
+                fee_payer_account,
+            } => {
+                account_shared_data.insert(*nonce.address(), nonce.account().clone());
+                account_shared_data.insert(fee_payer, fee_payer_account.clone());
+            }
+        }
+        return;
+    }
+
+    for (index, (pubkey, account_data)) in executed_transaction
+        .loaded_transaction
+        .accounts
+        .iter()
+        .enumerate()
+    {
+        if sanitized_transaction.is_writable(index) {
+            account_shared_data.insert(*pubkey, account_data.clone());
+        }
+    }
 }
 
 pub(crate) fn get_transaction_check_results(
@@ -239,135 +482,353 @@ pub(crate) fn get_transaction_check_results(
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::File, io::Read};
-
+    use rollup_test_utils::{counter_batch, funded_account, offramp, onramp, signed_transaction};
     use solana_sdk::{
-        account::Account,
-        instruction::{AccountMeta, Instruction, InstructionError},
-        loader_v4::{LoaderV4State, LoaderV4Status},
-        native_token::LAMPORTS_PER_SOL,
-        rent::Rent,
-        signature::Keypair,
-        signer::Signer,
-        system_instruction,
-        transaction::Transaction,
+        instruction::AccountMeta, native_token::LAMPORTS_PER_SOL, program_pack::Pack,
+        signature::Keypair, signer::Signer, system_instruction,
     };
-    use svm_runner_types::RampTx;
+    use spl_associated_token_account_client::address::get_associated_token_address;
+    use spl_token::state::Account as TokenAccount;
+    use svm_runner_types::{L1Message, RampTx};
 
     use super::*;
 
     #[test]
     fn test_runner() {
-        let test_input = create_test_input();
+        let test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
         let result = runner(&test_input);
         assert!(result.is_ok());
-        println!("result: {:?}", result.unwrap());
+        println!("result: {:?}", result.unwrap().state);
     }
 
-    fn create_test_input() -> ExecutionInput {
+    #[test]
+    fn test_config_hash() {
+        let test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let result = runner(&test_input).unwrap();
+        // Committed as is by the guest, and compared with the platform on L1
+        assert_eq!(result.config_hash, config_hash());
+    }
+
+    #[test]
+    fn test_l1_message() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let counter_program_id = test_input.accounts.0[2].0;
+        let pk_counter = test_input.accounts.0[3].0;
+        test_input.l1_messages.push(L1Message {
+            sender: Pubkey::new_unique(),
+            program_id: counter_program_id,
+            accounts: vec![AccountMeta::new(pk_counter, false)],
+            data: vec![],
+        });
+
+        let result = runner(&test_input).unwrap();
+        assert!(result.skipped_l1_messages.is_empty());
+        let (_, counter) = result
+            .state
+            .0
+            .iter()
+            .find(|(pk, _)| *pk == pk_counter)
+            .unwrap();
+        // Incremented by the message and by the transaction
+        assert_eq!(counter.data(), &[2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_l1_message_cannot_sign_for_the_bridge() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let pk_sender = test_input.accounts.0[0].0;
+        let l1_mint = Pubkey::new_unique();
+        let wrapped_mint = bridge::wrapped_mint_address(&l1_mint);
+        let pk_token_account = get_associated_token_address(&pk_sender, &wrapped_mint);
+        // Creates the wrapped mint and the token account
+        test_input.ramp_txs.push(RampTx {
+            is_onramp: true,
+            user: pk_sender,
+            amount: 1,
+            mint: Some(l1_mint),
+            decimals: 6,
+        });
+
+        // The bridge, authority of the wrapped mint, listed as a non-signer
+        // to be merged with the signing fee payer
+        let mint_to = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &wrapped_mint,
+            &pk_token_account,
+            &bridge::ID,
+            &[],
+            1_000_000,
+        )
+        .unwrap();
+        test_input.l1_messages.push(L1Message {
+            sender: Pubkey::new_unique(),
+            program_id: mint_to.program_id,
+            accounts: mint_to
+                .accounts
+                .into_iter()
+                .map(|meta| AccountMeta {
+                    is_signer: false,
+                    ..meta
+                })
+                .collect(),
+            data: mint_to.data,
+        });
+
+        let result = runner(&test_input).unwrap();
+        assert_eq!(result.skipped_l1_messages, vec![0]);
+        let (_, token_account) = result
+            .state
+            .0
+            .iter()
+            .find(|(pk, _)| *pk == pk_token_account)
+            .unwrap();
+        assert_eq!(
+            TokenAccount::unpack(token_account.data()).unwrap().amount,
+            1
+        );
+    }
+
+    #[test]
+    fn test_outbox() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
         let kp_sender = Keypair::new();
-        let kp_receiver = Keypair::new();
-        let pk_receiver = kp_receiver.pubkey();
-        let pk_sender = kp_sender.pubkey();
-
-        let counter_program_id = Keypair::new().pubkey();
-        let pk_counter = Keypair::new().pubkey();
-
-        let path = "../../counter-program/counter_program.so";
-        let mut file = File::open(path).expect("file open failed");
-        let mut elf_bytes = Vec::new();
-        file.read_to_end(&mut elf_bytes).unwrap();
-        let rent = Rent::default();
-        let account_size = LoaderV4State::program_data_offset().saturating_add(elf_bytes.len());
-        let mut program_account = AccountSharedData::new(
-            rent.minimum_balance(account_size),
-            account_size,
-            &loader_v4::id(),
+        let l1_program_id = Pubkey::new_unique();
+        test_input
+            .ramp_txs
+            .push(onramp(&kp_sender.pubkey(), LAMPORTS_PER_SOL));
+        test_input
+            .accounts
+            .0
+            .push((kp_sender.pubkey(), funded_account(0)));
+        test_input.txs.push(signed_transaction(
+            &[outbox::send_message(
+                &kp_sender.pubkey(),
+                &l1_program_id,
+                vec![AccountMeta::new(Pubkey::new_unique(), false)],
+                vec![1, 2, 3],
+            )],
+            &[&kp_sender],
+        ));
+
+        let result = runner(&test_input).unwrap();
+        assert_eq!(result.l2_messages.len(), 1);
+        assert_eq!(result.l2_messages[0].sender, kp_sender.pubkey());
+        assert_eq!(result.l2_messages[0].program_id, l1_program_id);
+        assert_eq!(result.l2_messages[0].data, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_withdrawals() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let pk_sender = test_input.accounts.0[0].0;
+        let pk_new_user = Pubkey::new_unique();
+        test_input.ramp_txs.extend([
+            // More than the balance, skipped
+            offramp(&pk_sender, 100 * LAMPORTS_PER_SOL),
+            offramp(&pk_sender, LAMPORTS_PER_SOL),
+            // Creates the account
+            onramp(&pk_new_user, LAMPORTS_PER_SOL),
+        ]);
+
+        let result = runner(&test_input).unwrap();
+        assert_eq!(result.skipped_ramp_txs, vec![1]);
+        assert_eq!(
+            result.withdrawals,
+            vec![Withdrawal {
+                user: pk_sender,
+                amount: LAMPORTS_PER_SOL,
+                mint: None,
+            }]
         );
-        let state = get_state_mut(program_account.data_as_mut_slice()).unwrap();
-        state.slot = 0;
-        state.authority_address_or_next_version = Pubkey::new_unique();
-        state.status = LoaderV4Status::Deployed;
-        program_account.data_as_mut_slice()[LoaderV4State::program_data_offset()..]
-            .copy_from_slice(&elf_bytes);
-
-        ExecutionInput {
-            accounts: RollupState(vec![
-                (
-                    pk_sender,
-                    Account {
-                        lamports: 0,
-                        data: vec![],
-                        owner: solana_system_program::id(),
-                        executable: false,
-                        rent_epoch: 0,
-                    }
-                    .into(),
-                ),
-                (
-                    pk_receiver,
-                    Account {
-                        lamports: 0,
-                        data: vec![],
-                        owner: solana_system_program::id(),
-                        executable: false,
-                        rent_epoch: 0,
-                    }
-                    .into(),
-                ),
-                (counter_program_id, program_account),
-                (
-                    pk_counter,
-                    Account {
-                        lamports: 100000,
-                        data: vec![0, 0, 0, 0],
-                        owner: counter_program_id,
-                        executable: false,
-                        rent_epoch: 0,
-                    }
-                    .into(),
-                ),
-            ]),
-            txs: vec![
-                Transaction::new_signed_with_payer(
-                    &[system_instruction::transfer(
-                        &pk_sender,
-                        &pk_receiver,
-                        LAMPORTS_PER_SOL,
-                    )],
-                    Some(&pk_sender),
-                    &[&kp_sender],
-                    Hash::new_from_array([7; 32]),
-                ),
-                Transaction::new_signed_with_payer(
-                    &[Instruction {
-                        program_id: counter_program_id,
-                        accounts: vec![AccountMeta::new(pk_counter, false)],
-                        data: vec![],
-                    }],
-                    Some(&pk_sender),
-                    &[&kp_sender],
-                    Hash::new_from_array([7; 32]),
-                ),
-            ],
-            ramp_txs: vec![RampTx {
-                is_onramp: true,
+        let (_, new_user) = result
+            .state
+            .0
+            .iter()
+            .find(|(pk, _)| *pk == pk_new_user)
+            .unwrap();
+        assert_eq!(new_user.lamports(), LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_token_ramps() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let pk_sender = test_input.accounts.0[0].0;
+        let l1_mint = Pubkey::new_unique();
+        let token_ramp = |is_onramp, amount| RampTx {
+            is_onramp,
+            user: pk_sender,
+            amount,
+            mint: Some(l1_mint),
+            decimals: 6,
+        };
+        // After the onramp of SOL paying for the token account
+        test_input.ramp_txs.extend([
+            token_ramp(true, 1_000),
+            // More than the balance, skipped
+            token_ramp(false, 2_000),
+            token_ramp(false, 400),
+        ]);
+
+        let result = runner(&test_input).unwrap();
+        assert_eq!(result.skipped_ramp_txs, vec![2]);
+        assert_eq!(
+            result.withdrawals,
+            vec![Withdrawal {
                 user: pk_sender,
-                amount: 10 * LAMPORTS_PER_SOL,
-            }],
-        }
+                amount: 400,
+                mint: Some(l1_mint),
+            }]
+        );
+        let pk_token_account =
+            get_associated_token_address(&pk_sender, &bridge::wrapped_mint_address(&l1_mint));
+        let (_, token_account) = result
+            .state
+            .0
+            .iter()
+            .find(|(pk, _)| *pk == pk_token_account)
+            .unwrap();
+        assert_eq!(
+            TokenAccount::unpack(token_account.data()).unwrap().amount,
+            600
+        );
     }
-    fn get_state_mut(data: &mut [u8]) -> Result<&mut LoaderV4State, InstructionError> {
-        unsafe {
-            let data = data
-                .get_mut(0..LoaderV4State::program_data_offset())
-                .ok_or(InstructionError::AccountDataTooSmall)?
-                .try_into()
-                .unwrap();
-            Ok(std::mem::transmute::<
-                &mut [u8; LoaderV4State::program_data_offset()],
-                &mut LoaderV4State,
-            >(data))
-        }
+
+    #[test]
+    fn test_token_onramp_to_funded_addresses() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let pk_sender = test_input.accounts.0[0].0;
+        let l1_mint = Pubkey::new_unique();
+        let wrapped_mint = bridge::wrapped_mint_address(&l1_mint);
+        let pk_token_account = get_associated_token_address(&pk_sender, &wrapped_mint);
+        // Lamports sent to both addresses on L2 before the first deposit
+        test_input.accounts.0.extend([
+            (wrapped_mint, funded_account(1_000)),
+            (pk_token_account, funded_account(LAMPORTS_PER_SOL)),
+        ]);
+        test_input.ramp_txs.push(RampTx {
+            is_onramp: true,
+            user: pk_sender,
+            amount: 1_000,
+            mint: Some(l1_mint),
+            decimals: 6,
+        });
+
+        let result = runner(&test_input).unwrap();
+        assert!(result.skipped_ramp_txs.is_empty());
+        assert!(result.withdrawals.is_empty());
+        let account = |pubkey: &Pubkey| {
+            let (_, account) = result.state.0.iter().find(|(pk, _)| pk == pubkey).unwrap();
+            account.clone()
+        };
+        let mint = account(&wrapped_mint);
+        assert_eq!(mint.owner(), &spl_token::id());
+        assert_eq!(
+            mint.lamports(),
+            Rent::default().minimum_balance(spl_token::state::Mint::LEN)
+        );
+        assert_eq!(
+            spl_token::state::Mint::unpack(mint.data()).unwrap().supply,
+            1_000
+        );
+        // Already above the rent exemption, so it keeps its lamports
+        let token_account = account(&pk_token_account);
+        assert_eq!(token_account.lamports(), LAMPORTS_PER_SOL);
+        assert_eq!(
+            TokenAccount::unpack(token_account.data()).unwrap().amount,
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_receipts() {
+        let mut test_input = counter_batch(&Keypair::new(), &Pubkey::new_unique());
+        let pk_receiver = test_input.accounts.0[1].0;
+        // Its fee payer has no lamports
+        let kp_unfunded = Keypair::new();
+        test_input.txs.push(signed_transaction(
+            &[system_instruction::transfer(
+                &kp_unfunded.pubkey(),
+                &pk_receiver,
+                LAMPORTS_PER_SOL,
+            )],
+            &[&kp_unfunded],
+        ));
+
+        let result = runner(&test_input).unwrap();
+        assert_eq!(result.receipts.len(), 3);
+        assert_eq!(
+            result.receipts[0].signature,
+            test_input.txs[0].signatures[0]
+        );
+        assert!(result.receipts[0].status.is_ok());
+        assert!(result.receipts[0].fee > 0);
+        assert!(result.receipts[1].status.is_ok());
+        assert!(!result.receipts[1].logs.is_empty());
+        assert_eq!(
+            result.receipts[2].status,
+            Err(TransactionError::AccountNotFound)
+        );
+        assert_eq!(result.receipts[2].fee, 0);
+        assert!(result.receipts[2].was_dropped());
+        assert!(!result.receipts[0].was_dropped());
+
+        // Only the first transfer was applied
+        let (_, receiver) = result
+            .state
+            .0
+            .iter()
+            .find(|(pk, _)| *pk == pk_receiver)
+            .unwrap();
+        assert_eq!(receiver.lamports(), LAMPORTS_PER_SOL);
+    }
+
+    #[test]
+    fn test_failed_transaction() {
+        let kp_sender = Keypair::new();
+        let pk_receiver = Pubkey::new_unique();
+        let mut test_input = counter_batch(&kp_sender, &pk_receiver);
+        // The first transfer succeeds, the second is more than the balance
+        test_input.txs = vec![signed_transaction(
+            &[
+                system_instruction::transfer(&kp_sender.pubkey(), &pk_receiver, LAMPORTS_PER_SOL),
+                system_instruction::transfer(
+                    &kp_sender.pubkey(),
+                    &pk_receiver,
+                    100 * LAMPORTS_PER_SOL,
+                ),
+            ],
+            &[&kp_sender],
+        )];
+        // Malformed, it cannot be sanitized
+        let mut malformed = signed_transaction(
+            &[system_instruction::transfer(
+                &kp_sender.pubkey(),
+                &pk_receiver,
+                LAMPORTS_PER_SOL,
+            )],
+            &[&kp_sender],
+        );
+        malformed.message.instructions[0].program_id_index = 99;
+        test_input.txs.push(malformed.clone());
+
+        let result = runner(&test_input).unwrap();
+        let receipt = &result.receipts[0];
+        assert!(receipt.status.is_err());
+        assert!(receipt.fee > 0);
+        assert_eq!(
+            result.receipts[1],
+            TransactionReceipt::dropped(malformed.signatures[0], TransactionError::SanitizeFailure)
+        );
+
+        // Only the fee was charged, the first transfer was rolled back
+        let lamports = |pubkey: &Pubkey| {
+            let (_, account) = result.state.0.iter().find(|(pk, _)| pk == pubkey).unwrap();
+            account.lamports()
+        };
+        assert_eq!(
+            lamports(&kp_sender.pubkey()),
+            10 * LAMPORTS_PER_SOL - receipt.fee
+        );
+        assert_eq!(lamports(&pk_receiver), 0);
     }
 }
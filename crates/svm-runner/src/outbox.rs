@@ -0,0 +1,94 @@
+//! Builtin program collecting the L2 to L1 messages of a batch.
+//!
+//! Programs write a message by invoking the outbox with the message sender as
+//! signer, usually a PDA of the calling program. The messages land in the
+//! outbox account, which the runner empties at the start of every batch and
+//! reads back once the batch is executed.
+
+use solana_program_runtime::declare_process_instruction;
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount},
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use svm_runner_types::L2Message;
+
+solana_sdk::declare_id!("ZkMessageBox1111111111111111111111111111111");
+
+pub const OUTBOX_SEED: &[u8] = b"outbox";
+
+const DEFAULT_COMPUTE_UNITS: u64 = 150;
+
+/// Largest account size the runtime allows, `MAX_PERMITTED_DATA_LENGTH`
+const OUTBOX_CAPACITY: usize = 10 * 1024 * 1024;
+
+/// The account the outbox program writes messages to.
+pub fn outbox_address() -> Pubkey {
+    Pubkey::find_program_address(&[OUTBOX_SEED], &ID).0
+}
+
+/// Writes a message executing `program_id` on L1, signed there by the alias of `sender`.
+pub fn send_message(
+    sender: &Pubkey,
+    program_id: &Pubkey,
+    accounts: Vec<AccountMeta>,
+    data: Vec<u8>,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        ID,
+        &(program_id, accounts, data),
+        vec![
+            AccountMeta::new(outbox_address(), false),
+            AccountMeta::new_readonly(*sender, true),
+        ],
+    )
+}
+
+/// An empty outbox, funded to stay rent exempt whatever the number of messages.
+pub(crate) fn outbox_account() -> AccountSharedData {
+    AccountSharedData::new(Rent::default().minimum_balance(OUTBOX_CAPACITY), 0, &ID)
+}
+
+/// Messages written to `account`.
+pub(crate) fn read_messages(account: &AccountSharedData) -> Vec<L2Message> {
+    if account.data().is_empty() {
+        return vec![];
+    }
+    bincode::deserialize(account.data()).unwrap()
+}
+
+declare_process_instruction!(Entrypoint, DEFAULT_COMPUTE_UNITS, |invoke_context| {
+    let transaction_context = &invoke_context.transaction_context;
+    let instruction_context = transaction_context.get_current_instruction_context()?;
+    let (program_id, accounts, data): (Pubkey, Vec<AccountMeta>, Vec<u8>) =
+        bincode::deserialize(instruction_context.get_instruction_data())
+            .map_err(|_| InstructionError::InvalidInstructionData)?;
+
+    let sender = instruction_context.try_borrow_instruction_account(transaction_context, 1)?;
+    if !sender.is_signer() {
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    let sender = *sender.get_key();
+
+    let mut outbox = instruction_context.try_borrow_instruction_account(transaction_context, 0)?;
+    if *outbox.get_key() != outbox_address() {
+        return Err(InstructionError::InvalidArgument);
+    }
+
+    let mut messages: Vec<L2Message> = if outbox.get_data().is_empty() {
+        vec![]
+    } else {
+        bincode::deserialize(outbox.get_data()).map_err(|_| InstructionError::InvalidAccountData)?
+    };
+    messages.push(L2Message {
+        sender,
+        program_id,
+        accounts,
+        data,
+    });
+    let data = bincode::serialize(&messages).map_err(|_| InstructionError::InvalidAccountData)?;
+    outbox.set_data_from_slice(&data)?;
+
+    Ok(())
+});
@@ -0,0 +1,113 @@
+//! Builders for program accounts of the loaders registered by the runner.
+
+use solana_sdk::{
+    account::{Account, AccountSharedData, WritableAccount},
+    bpf_loader, bpf_loader_upgradeable,
+    bpf_loader_upgradeable::UpgradeableLoaderState,
+    loader_v4::{self, LoaderV4State, LoaderV4Status},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+
+/// A program owned by `BPFLoader2111111111111111111111111111111111`.
+pub fn program_account_v2(elf: &[u8]) -> AccountSharedData {
+    Account {
+        lamports: Rent::default().minimum_balance(elf.len()),
+        data: elf.to_vec(),
+        owner: bpf_loader::id(),
+        executable: true,
+        rent_epoch: 0,
+    }
+    .into()
+}
+
+/// The program account and its program data account for the upgradeable loader.
+pub fn program_accounts_v3(
+    program_id: &Pubkey,
+    elf: &[u8],
+    upgrade_authority_address: Option<Pubkey>,
+) -> [(Pubkey, AccountSharedData); 2] {
+    let rent = Rent::default();
+    let (programdata_address, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+
+    let program_data = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address,
+    })
+    .unwrap();
+    let program_account = Account {
+        lamports: rent.minimum_balance(program_data.len()),
+        data: program_data,
+        owner: bpf_loader_upgradeable::id(),
+        executable: true,
+        rent_epoch: 0,
+    };
+
+    let mut programdata_data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address,
+    })
+    .unwrap();
+    // A missing authority serializes shorter than the fixed metadata size
+    programdata_data.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    programdata_data.extend_from_slice(elf);
+    let programdata_account = Account {
+        lamports: rent.minimum_balance(programdata_data.len()),
+        data: programdata_data,
+        owner: bpf_loader_upgradeable::id(),
+        executable: false,
+        rent_epoch: 0,
+    };
+
+    [
+        (*program_id, program_account.into()),
+        (programdata_address, programdata_account.into()),
+    ]
+}
+
+/// A deployed program owned by `LoaderV411111111111111111111111111111111111`.
+pub fn program_account_v4(elf: &[u8], authority: Pubkey) -> AccountSharedData {
+    let rent = Rent::default();
+    let account_size = LoaderV4State::program_data_offset().saturating_add(elf.len());
+    let mut program_account = AccountSharedData::new(
+        rent.minimum_balance(account_size),
+        account_size,
+        &loader_v4::id(),
+    );
+
+    // `LoaderV4State` is `repr(C)`: slot, authority, status
+    let data = program_account.data_as_mut_slice();
+    data[0..8].copy_from_slice(&0u64.to_le_bytes());
+    data[8..40].copy_from_slice(authority.as_ref());
+    data[40..48].copy_from_slice(&(LoaderV4Status::Deployed as u64).to_le_bytes());
+    data[LoaderV4State::program_data_offset()..].copy_from_slice(elf);
+
+    program_account
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::account::ReadableAccount;
+
+    use super::*;
+
+    #[test]
+    fn test_program_accounts_v3() {
+        let program_id = Pubkey::new_unique();
+        let [(pk_program, program), (pk_programdata, programdata)] =
+            program_accounts_v3(&program_id, &[1, 2, 3], None);
+
+        assert_eq!(pk_program, program_id);
+        assert!(program.executable());
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(program.data()).unwrap(),
+            UpgradeableLoaderState::Program {
+                programdata_address: pk_programdata
+            }
+        );
+        assert_eq!(
+            &programdata.data()[UpgradeableLoaderState::size_of_programdata_metadata()..],
+            &[1, 2, 3]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! SPL programs shipped with the rollup at their canonical program IDs.
+//!
+//! The runner loads the ones a batch references when the state does not
+//! provide its own deployment, so they are available without being part of
+//! every `ExecutionInput`. Where the ELFs come from is documented in
+//! `programs/README.md`.
+
+use solana_sdk::{account::AccountSharedData, bpf_loader, bpf_loader_upgradeable, pubkey::Pubkey};
+use std::sync::OnceLock;
+
+use crate::programs::{program_account_v2, program_accounts_v3};
+
+pub mod spl_token {
+    solana_sdk::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+}
+
+pub mod spl_token_2022 {
+    solana_sdk::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
+pub mod spl_associated_token_account {
+    solana_sdk::declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
+/// Program id, loader, ELF and SHA-256 of the ELF as listed in
+/// `programs/README.md`, using the same loaders as mainnet
+pub(crate) static SPL_PROGRAMS: &[(Pubkey, Pubkey, &[u8], &str)] = &[
+    (
+        spl_token::ID,
+        bpf_loader::ID,
+        include_bytes!("../programs/spl_token-3.5.0.so"),
+        "18264f491c7e0ad056dd36f42f8de6d1fedf9f044d1f521e714b4dc6b61594b6",
+    ),
+    (
+        spl_token_2022::ID,
+        bpf_loader_upgradeable::ID,
+        include_bytes!("../programs/spl_token_2022-1.0.0.so"),
+        "47181a65d6ee7d7a100480db2efaa21fcfff2be1af49155c30975e9fe5c0c9d6",
+    ),
+    (
+        spl_associated_token_account::ID,
+        bpf_loader::ID,
+        include_bytes!("../programs/spl_associated_token_account-1.1.1.so"),
+        "e5e7aed11ad3969eea2aa76c8b4d2e73ea25be7e6b5cce989b7710cf5452496e",
+    ),
+];
+
+/// An SPL program and the accounts deploying it
+pub struct SplProgram {
+    pub program_id: Pubkey,
+    /// The program account, followed by its program data account for the
+    /// upgradeable loader
+    pub accounts: Vec<(Pubkey, AccountSharedData)>,
+}
+
+/// SPL Token, Token-2022 and the Associated Token Account program.
+///
+/// The accounts are built on first use. Account data is reference counted, so
+/// cloning them afterwards does not copy the ELFs.
+pub fn spl_programs() -> &'static [SplProgram] {
+    static PROGRAMS: OnceLock<Vec<SplProgram>> = OnceLock::new();
+    PROGRAMS.get_or_init(|| {
+        SPL_PROGRAMS
+            .iter()
+            .map(|(program_id, loader_id, elf, _)| SplProgram {
+                program_id: *program_id,
+                accounts: if *loader_id == bpf_loader_upgradeable::ID {
+                    program_accounts_v3(program_id, elf, None).to_vec()
+                } else {
+                    vec![(*program_id, program_account_v2(elf))]
+                },
+            })
+            .collect()
+    })
+}
+
+/// The accounts deploying SPL Token, Token-2022 and the Associated Token Account program.
+pub fn spl_program_accounts() -> Vec<(Pubkey, AccountSharedData)> {
+    spl_programs()
+        .iter()
+        .flat_map(|program| program.accounts.iter().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::hash::hash;
+
+    use super::*;
+
+    #[test]
+    fn test_spl_program_checksums() {
+        for (program_id, _, elf, checksum) in SPL_PROGRAMS {
+            let digest: String = hash(elf)
+                .to_bytes()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            assert_eq!(digest, *checksum, "{}", program_id);
+        }
+    }
+}
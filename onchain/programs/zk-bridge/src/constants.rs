@@ -4,7 +4,29 @@ use anchor_lang::prelude::*;
 pub const PLATFORM_SEED_PREFIX: &[u8] = b"platform";
 
 #[constant]
-pub const COMMIT_SEED_PREFIX: &[u8] = b"commit";
+pub const RAMP_PAGE_SEED_PREFIX: &[u8] = b"ramp_page";
 
+/// Entries per ramp page
 #[constant]
-pub const RAMP_SEED_PREFIX: &[u8] = b"ramp";
+pub const RAMP_PAGE_CAPACITY: u64 = 32;
+
+#[constant]
+pub const MESSAGE_SEED_PREFIX: &[u8] = b"message";
+
+#[constant]
+pub const BATCH_SEED_PREFIX: &[u8] = b"batch";
+
+#[constant]
+pub const BATCH_DATA_SEED_PREFIX: &[u8] = b"batch_data";
+
+#[constant]
+pub const EXECUTED_MESSAGE_SEED_PREFIX: &[u8] = b"executed_message";
+
+#[constant]
+pub const L2_SENDER_SEED_PREFIX: &[u8] = b"l2_sender";
+
+#[constant]
+pub const WITHDRAWAL_CLAIM_SEED_PREFIX: &[u8] = b"withdrawal_claim";
+
+#[constant]
+pub const VAULT_SEED_PREFIX: &[u8] = b"vault";
@@ -12,4 +12,26 @@ pub enum PlatformError {
     InvalidProof,
     #[msg("Missing ramp txs")]
     MissingRampTxs,
+    #[msg("Ramp txs do not match the platform")]
+    InvalidRampTxs,
+    #[msg("Invalid ramp page")]
+    InvalidRampPage,
+    #[msg("Invalid withdrawal proof")]
+    InvalidWithdrawalProof,
+    #[msg("Invalid message queue")]
+    InvalidMessageQueue,
+    #[msg("Invalid message proof")]
+    InvalidMessageProof,
+    #[msg("Invalid message signer")]
+    InvalidMessageSigner,
+    #[msg("Input state hash does not match the last state hash")]
+    InvalidInputStateHash,
+    #[msg("Invalid batch number")]
+    InvalidBatchNumber,
+    #[msg("Invalid config hash")]
+    InvalidConfigHash,
+    #[msg("Invalid batch data")]
+    InvalidBatchData,
+    #[msg("Batch data was not published")]
+    MissingBatchData,
 }
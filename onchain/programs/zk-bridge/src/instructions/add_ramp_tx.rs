@@ -1,5 +1,4 @@
 use crate::constants::*;
-use crate::errors::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
@@ -17,9 +16,6 @@ pub struct AddRampTx<'info> {
     pub ramper: Signer<'info>,
     #[account(
         mut,
-        realloc = 8 + Platform::INIT_SPACE + (platform.ramp_txs.len() + 1) * Ramp::INIT_SPACE,
-        realloc::payer = ramper,
-        realloc::zero = false,
         seeds = [
             PLATFORM_SEED_PREFIX,
             platform.id.as_ref(),
@@ -30,25 +26,20 @@ pub struct AddRampTx<'info> {
     #[account(
         init_if_needed,
         payer = ramper,
-        space = 8 + Ramp::INIT_SPACE,
-        seeds = [RAMP_SEED_PREFIX, platform.id.as_ref(), ramper.key().as_ref()],
+        space = 8 + RampPage::INIT_SPACE,
+        seeds = [
+            RAMP_PAGE_SEED_PREFIX,
+            platform.id.as_ref(),
+            platform.tail_page_index().to_le_bytes().as_ref(),
+        ],
         bump
     )]
-    pub ramp: Account<'info, Ramp>,
+    pub ramp_page: Account<'info, RampPage>,
     pub system_program: Program<'info, System>,
 }
 
 impl AddRampTx<'_> {
     pub fn handle(ctx: Context<Self>, args: AddRampTxArgs) -> Result<()> {
-        if ctx.accounts.ramp.ramper.eq(&Pubkey::default()) {
-            ctx.accounts.ramp.set_inner(Ramp {
-                bump: ctx.bumps.ramp,
-                ramper: ctx.accounts.ramper.key(),
-                current_state_hash: ctx.accounts.platform.last_state_hash,
-                pending_withdraw: 0,
-            });
-        }
-
         msg!("args.amount: {}", args.amount);
 
         if args.is_onramp {
@@ -64,21 +55,19 @@ impl AddRampTx<'_> {
                 ),
                 args.amount,
             )?;
-        } else {
-            ctx.accounts.platform.withdraw += args.amount;
-            if ctx.accounts.platform.withdraw > ctx.accounts.platform.deposit {
-                return Err(PlatformError::InsufficientDeposits.into());
-            }
-
-            ctx.accounts.ramp.current_state_hash = ctx.accounts.platform.last_state_hash;
-            ctx.accounts.ramp.pending_withdraw += args.amount;
         }
 
-        ctx.accounts.platform.ramp_txs.push(RampTx {
-            is_onramp: args.is_onramp,
-            amount: args.amount,
-            user: ctx.accounts.ramper.key(),
-        });
+        ctx.accounts.platform.push_ramp_tx(
+            &mut ctx.accounts.ramp_page,
+            ctx.bumps.ramp_page,
+            RampTx {
+                is_onramp: args.is_onramp,
+                amount: args.amount,
+                user: ctx.accounts.ramper.key(),
+                mint: None,
+                decimals: 9,
+            },
+        );
 
         Ok(())
     }
@@ -0,0 +1,98 @@
+use crate::constants::*;
+use crate::errors::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddTokenRampTxArgs {
+    pub is_onramp: bool,
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(args: AddTokenRampTxArgs)]
+pub struct AddTokenRampTx<'info> {
+    #[account(mut)]
+    pub ramper: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PLATFORM_SEED_PREFIX,
+            platform.id.as_ref(),
+        ],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    #[account(
+        init_if_needed,
+        payer = ramper,
+        space = 8 + RampPage::INIT_SPACE,
+        seeds = [
+            RAMP_PAGE_SEED_PREFIX,
+            platform.id.as_ref(),
+            platform.tail_page_index().to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub ramp_page: Account<'info, RampPage>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = ramper,
+        token::mint = mint,
+        token::authority = platform,
+        seeds = [
+            VAULT_SEED_PREFIX,
+            platform.id.as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = ramper,
+    )]
+    pub ramper_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl AddTokenRampTx<'_> {
+    pub fn handle(ctx: Context<Self>, args: AddTokenRampTxArgs) -> Result<()> {
+        if args.is_onramp {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.ramper_token_account.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                        authority: ctx.accounts.ramper.to_account_info(),
+                    },
+                ),
+                args.amount,
+            )?;
+        } else {
+            // Cannot withdraw more tokens than the vault holds
+            if args.amount > ctx.accounts.vault.amount {
+                return Err(PlatformError::InsufficientDeposits.into());
+            }
+        }
+
+        ctx.accounts.platform.push_ramp_tx(
+            &mut ctx.accounts.ramp_page,
+            ctx.bumps.ramp_page,
+            RampTx {
+                is_onramp: args.is_onramp,
+                amount: args.amount,
+                user: ctx.accounts.ramper.key(),
+                mint: Some(ctx.accounts.mint.key()),
+                decimals: ctx.accounts.mint.decimals,
+            },
+        );
+
+        Ok(())
+    }
+}
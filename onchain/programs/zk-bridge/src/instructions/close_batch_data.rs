@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::*;
+use crate::errors::*;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CloseBatchData<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+    #[account(
+        seeds = [
+            PLATFORM_SEED_PREFIX,
+            platform.id.as_ref(),
+        ],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    #[account(
+        mut,
+        close = publisher,
+        has_one = publisher,
+        seeds = [
+            BATCH_DATA_SEED_PREFIX,
+            platform.id.as_ref(),
+            publisher.key().as_ref(),
+            batch_data.txs_hash.as_ref(),
+        ],
+        bump = batch_data.bump
+    )]
+    pub batch_data: Account<'info, BatchData>,
+}
+
+impl CloseBatchData<'_> {
+    pub fn handle(ctx: Context<Self>) -> Result<()> {
+        // Sealed data may back a proven batch, and is what the rollup state
+        // is rebuilt from without the sequencer
+        if ctx.accounts.batch_data.sealed {
+            return Err(PlatformError::InvalidBatchData.into());
+        }
+
+        Ok(())
+    }
+}
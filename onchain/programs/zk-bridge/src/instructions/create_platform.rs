@@ -7,6 +7,7 @@ use crate::state::*;
 pub struct CreatePlatformArgs {
     pub id: Pubkey,
     pub initial_state_hash: [u8; 32],
+    pub config_hash: [u8; 32],
 }
 
 #[derive(Accounts)]
@@ -35,9 +36,18 @@ impl CreatePlatform<'_> {
             id: args.id,
             sequencer: ctx.accounts.sequencer.key(),
             last_state_hash: args.initial_state_hash,
-            ramp_txs: vec![],
+            config_hash: args.config_hash,
+            ramp_head: 0,
+            ramp_tail: 0,
+            ramp_head_hash: [0; 32],
+            ramp_txs_hash: [0; 32],
             deposit: 0,
             withdraw: 0,
+            message_head: 0,
+            message_tail: 0,
+            message_head_hash: [0; 32],
+            message_queue_hash: [0; 32],
+            batch_number: 0,
         });
 
         Ok(())
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+use crate::constants::*;
+use crate::errors::*;
+use crate::state::*;
+use crate::utils::hashv_bytes;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ExecuteL2MessageArgs {
+    pub batch_number: u64,
+    /// Position of the message in the outbox of the batch
+    pub index: u64,
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    pub accounts: Vec<MessageAccountMeta>,
+    pub data: Vec<u8>,
+    /// Merkle proof of the message against the outbox root of the batch
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: ExecuteL2MessageArgs)]
+pub struct ExecuteL2Message<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [
+            PLATFORM_SEED_PREFIX,
+            platform.id.as_ref(),
+        ],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    #[account(
+        seeds = [
+            BATCH_SEED_PREFIX,
+            platform.id.as_ref(),
+            args.batch_number.to_le_bytes().as_ref(),
+        ],
+        bump = batch.bump
+    )]
+    pub batch: Account<'info, Batch>,
+    /// Fails to be created again if the message was already executed
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ExecutedMessage::INIT_SPACE,
+        seeds = [
+            EXECUTED_MESSAGE_SEED_PREFIX,
+            platform.id.as_ref(),
+            args.batch_number.to_le_bytes().as_ref(),
+            args.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub executed_message: Account<'info, ExecutedMessage>,
+    /// CHECK: L1 alias of the L2 sender, only used as a signer
+    #[account(
+        seeds = [
+            L2_SENDER_SEED_PREFIX,
+            platform.id.as_ref(),
+            args.sender.as_ref(),
+        ],
+        bump
+    )]
+    pub l2_sender: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteL2Message<'info> {
+    pub fn handle(ctx: Context<'_, '_, '_, 'info, Self>, args: ExecuteL2MessageArgs) -> Result<()> {
+        let message = onchain_types::L2Message {
+            sender: onchain_types::Pubkey(args.sender.to_bytes()),
+            program_id: onchain_types::Pubkey(args.program_id.to_bytes()),
+            accounts: args.accounts.iter().map(Into::into).collect(),
+            data: args.data.clone(),
+        };
+        let leaf = message.leaf_hash(hashv_bytes, args.index);
+        if !onchain_types::merkle::verify(
+            hashv_bytes,
+            leaf,
+            args.index,
+            &args.proof,
+            &ctx.accounts.batch.outbox_root,
+        ) {
+            return Err(PlatformError::InvalidMessageProof.into());
+        }
+
+        // The alias of the sender is the only signer a message can ask for
+        let l2_sender = ctx.accounts.l2_sender.key();
+        if args
+            .accounts
+            .iter()
+            .any(|meta| meta.is_signer && meta.pubkey != l2_sender)
+        {
+            return Err(PlatformError::InvalidMessageSigner.into());
+        }
+
+        let instruction = Instruction {
+            program_id: args.program_id,
+            accounts: args
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: args.data,
+        };
+
+        let mut account_infos = ctx.remaining_accounts.to_vec();
+        account_infos.push(ctx.accounts.l2_sender.to_account_info());
+
+        let platform_id = ctx.accounts.platform.id;
+        invoke_signed(
+            &instruction,
+            &account_infos,
+            &[&[
+                L2_SENDER_SEED_PREFIX,
+                platform_id.as_ref(),
+                args.sender.as_ref(),
+                &[ctx.bumps.l2_sender],
+            ]],
+        )?;
+
+        ctx.accounts.executed_message.bump = ctx.bumps.executed_message;
+
+        Ok(())
+    }
+}
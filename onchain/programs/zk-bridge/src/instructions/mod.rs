@@ -1,11 +1,21 @@
 pub mod add_ramp_tx;
+pub mod add_token_ramp_tx;
+pub mod close_batch_data;
 pub mod create_platform;
+pub mod execute_l2_message;
 pub mod prove;
-pub mod upload_commit;
+pub mod publish_batch_data;
+pub mod send_message;
 pub mod withdraw;
+pub mod withdraw_token;
 
 pub use add_ramp_tx::*;
+pub use add_token_ramp_tx::*;
+pub use close_batch_data::*;
 pub use create_platform::*;
+pub use execute_l2_message::*;
 pub use prove::*;
-pub use upload_commit::*;
+pub use publish_batch_data::*;
+pub use send_message::*;
 pub use withdraw::*;
+pub use withdraw_token::*;
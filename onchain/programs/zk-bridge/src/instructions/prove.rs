@@ -1,86 +1,190 @@
 use anchor_lang::prelude::*;
-use onchain_types::CommittedValues;
+use onchain_types::{PublicValues, ZK_BRIDGE_VKEY_HASH};
 
 use crate::constants::*;
 use crate::errors::*;
 use crate::state::*;
 
-/// Derived as follows:
-///
-/// ```
-/// let client = sp1_sdk::ProverClient::new();
-/// let (pk, vk) = client.setup(YOUR_ELF_HERE);
-/// let vkey_hash = vk.bytes32();
-/// ```
-const ZK_BRIDGE_VKEY_HASH: &str =
-    "0x00e6c119f877ce29467d89e62b47f983177b85fddbd90ce988b6303b2f5d7f9b";
-// "0x00508568011475c128053532cd7c60b9791f6edd5437e094c038a7445fba383d";
-
-// #[derive(AnchorDeserialize, AnchorSerialize)]
-// pub struct SP1Groth16Proof {
-//     pub proof: Vec<u8>,
-//     pub sp1_public_inputs: Vec<u8>,
-// }
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProveArgs {
+    /// Groth16 proof
+    pub proof: Vec<u8>,
+    /// `PublicValues` committed by the guest, `PublicValues::SIZE` bytes
+    pub public_values: Vec<u8>,
+}
 
 #[derive(Accounts)]
 pub struct Prove<'info> {
     #[account(mut)]
     pub prover: Signer<'info>,
     #[account(
+        mut,
         seeds = [
-            COMMIT_SEED_PREFIX,
+            PLATFORM_SEED_PREFIX,
             platform.id.as_ref(),
-            prover.key().as_ref(),
         ],
-        bump = commit.bump
+        bump = platform.bump
     )]
-    pub commit: Account<'info, Commit>,
+    pub platform: Account<'info, Platform>,
     #[account(
-        mut,
+        init,
+        payer = prover,
+        space = 8 + Batch::INIT_SPACE,
         seeds = [
-            PLATFORM_SEED_PREFIX,
+            BATCH_SEED_PREFIX,
             platform.id.as_ref(),
+            platform.batch_number.to_le_bytes().as_ref(),
         ],
-        bump = platform.bump
+        bump
     )]
-    pub platform: Account<'info, Platform>,
+    pub batch: Account<'info, Batch>,
+    /// Transactions of the batch, published with `publish_batch_data`
+    pub batch_data: Account<'info, BatchData>,
+    /// Page holding the last ramp tx consumed by the batch, if any
+    pub ramp_page: Option<Account<'info, RampPage>>,
+    /// Last L1 message executed by the batch, if any
+    pub last_message: Option<Account<'info, Message>>,
     pub system_program: Program<'info, System>,
 }
 
 impl Prove<'_> {
-    pub fn handle(ctx: Context<Self>, proof: Vec<u8>) -> Result<()> {
-        let vk = sp1_solana::GROTH16_VK_4_0_0_RC3_BYTES;
-        sp1_solana::verify_proof(&proof, &ctx.accounts.commit.data, ZK_BRIDGE_VKEY_HASH, vk)
-            .map_err(|_| PlatformError::InvalidProof)?;
+    pub fn handle(ctx: Context<Self>, args: ProveArgs) -> Result<()> {
+        if !is_mock_proof(&args.proof) {
+            let vk = sp1_solana::GROTH16_VK_4_0_0_RC3_BYTES;
+            sp1_solana::verify_proof(&args.proof, &args.public_values, ZK_BRIDGE_VKEY_HASH, vk)
+                .map_err(|_| PlatformError::InvalidProof)?;
+        }
 
-        let committed_values: CommittedValues =
-            bincode::deserialize(ctx.accounts.commit.data.as_slice()).unwrap();
+        let public_values =
+            PublicValues::from_bytes(&args.public_values).ok_or(PlatformError::InvalidProofData)?;
 
-        // msg!("commit data: {:?}", ctx.accounts.commit.data);
-        // msg!("commit data len: {}", ctx.accounts.commit.data.len());
+        let platform = &ctx.accounts.platform;
 
-        // Check that ramps txs match the ones in the platform
-        // Currently only check the count, could be improved to a hash of all txs
-        if committed_values.input.ramp_txs.len() != ctx.accounts.platform.ramp_txs.len() {
-            return Err(PlatformError::MissingRampTxs.into());
+        // The proof must start from the state the platform is at, otherwise
+        // any valid proof, including an old one, could overwrite it
+        if public_values.pre_state_hash != platform.last_state_hash {
+            return Err(PlatformError::InvalidInputStateHash.into());
+        }
+        if public_values.batch_number != platform.batch_number {
+            return Err(PlatformError::InvalidBatchNumber.into());
+        }
+        if public_values.config_hash != platform.config_hash {
+            return Err(PlatformError::InvalidConfigHash.into());
         }
 
-        // Empty pending ramp txs
-        ctx.accounts.platform.ramp_txs = vec![];
+        // The transactions must be available to rebuild the state without the sequencer
+        let batch_data = &ctx.accounts.batch_data;
+        if !batch_data.sealed
+            || batch_data.platform != platform.id
+            || batch_data.txs_hash != public_values.txs_hash
+        {
+            return Err(PlatformError::MissingBatchData.into());
+        }
 
-        // This can currently brick the platform, there should be a limit in number of ramp txs
-        for ramp_tx in committed_values
-            .input
-            .ramp_txs
-            .iter()
-            .filter(|ramp_tx| !ramp_tx.is_onramp)
+        // Check that the ramp txs are a prefix of the pending ones. The prover
+        // picks how many it consumes, so a batch stays bounded however long
+        // the queue grows.
+        let consumed = public_values.ramp_txs_count;
+        if consumed > platform.ramp_tail - platform.ramp_head {
+            return Err(PlatformError::MissingRampTxs.into());
+        }
+        if public_values.ramp_txs_start_hash != platform.ramp_head_hash {
+            return Err(PlatformError::InvalidRampTxs.into());
+        }
+        let expected_ramp_txs_hash = if consumed == 0 {
+            platform.ramp_head_hash
+        } else {
+            let last = platform.ramp_head + consumed - 1;
+            let page = ctx
+                .accounts
+                .ramp_page
+                .as_ref()
+                .ok_or(PlatformError::InvalidRampPage)?;
+            if page.platform != platform.id || page.index != last / RAMP_PAGE_CAPACITY {
+                return Err(PlatformError::InvalidRampPage.into());
+            }
+            page.entries[(last % RAMP_PAGE_CAPACITY) as usize].hash
+        };
+        if public_values.ramp_txs_hash != expected_ramp_txs_hash {
+            return Err(PlatformError::InvalidRampTxs.into());
+        }
+
+        // Same for the L1 messages, anyone can send one while a proof is
+        // being generated
+        let executed = public_values.l1_messages_count;
+        if executed > platform.message_tail - platform.message_head
+            || public_values.l1_messages_start_hash != platform.message_head_hash
         {
-            ctx.accounts.platform.withdraw += ramp_tx.amount;
+            return Err(PlatformError::InvalidMessageQueue.into());
         }
+        let expected_l1_messages_hash = if executed == 0 {
+            platform.message_head_hash
+        } else {
+            let message = ctx
+                .accounts
+                .last_message
+                .as_ref()
+                .ok_or(PlatformError::InvalidMessageQueue)?;
+            if message.platform != platform.id
+                || message.index != platform.message_head + executed - 1
+            {
+                return Err(PlatformError::InvalidMessageQueue.into());
+            }
+            message.hash
+        };
+        if public_values.l1_messages_hash != expected_l1_messages_hash {
+            return Err(PlatformError::InvalidMessageQueue.into());
+        }
+
+        let platform = &mut ctx.accounts.platform;
+
+        // Pop the executed L1 messages
+        platform.message_head += executed;
+        platform.message_head_hash = public_values.l1_messages_hash;
+
+        // Pop the consumed ramp txs
+        platform.ramp_head += consumed;
+        platform.ramp_head_hash = public_values.ramp_txs_hash;
 
         // Update the platform state
-        ctx.accounts.platform.last_state_hash = committed_values.output;
+        platform.last_state_hash = public_values.post_state_hash;
+
+        // Keep the outbox and withdrawals of the batch for execute_l2_message and withdraw
+        ctx.accounts.batch.set_inner(Batch {
+            number: platform.batch_number,
+            state_hash: public_values.post_state_hash,
+            txs_hash: public_values.txs_hash,
+            outbox_root: public_values.outbox_root,
+            withdrawals_root: public_values.withdrawals_root,
+            ramp_head: platform.ramp_head,
+            message_head: platform.message_head,
+            batch_data: ctx.accounts.batch_data.key(),
+            bump: ctx.bumps.batch,
+        });
+        platform.batch_number += 1;
 
         Ok(())
     }
 }
+
+/// Program ID of zk-bridge for `localnet` in `Anchor.toml`. Mock proofs are
+/// refused at any other ID, so releases must be deployed under their own ID.
+#[cfg(feature = "mock-proofs")]
+const LOCALNET_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("Bq5sTpeHWMCety13tmZqgYvDJoAAC4mAwBn33mZmuc41");
+
+/// Whether `proof` is an empty proof of the SP1 mock prover, which a build with
+/// the `mock-proofs` feature accepts at the localnet program ID only.
+#[cfg(feature = "mock-proofs")]
+fn is_mock_proof(proof: &[u8]) -> bool {
+    if !proof.is_empty() || crate::ID != LOCALNET_PROGRAM_ID {
+        return false;
+    }
+    msg!("Accepting a mock proof");
+    true
+}
+
+#[cfg(not(feature = "mock-proofs"))]
+fn is_mock_proof(_proof: &[u8]) -> bool {
+    false
+}
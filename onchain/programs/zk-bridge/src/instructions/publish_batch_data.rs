@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::constants::*;
+use crate::errors::*;
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PublishBatchDataArgs {
+    pub txs_hash: [u8; 32],
+    /// Length of the complete data
+    pub size: u64,
+    /// Next chunk of the data
+    pub data: Vec<u8>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: PublishBatchDataArgs)]
+pub struct PublishBatchData<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+    #[account(
+        seeds = [
+            PLATFORM_SEED_PREFIX,
+            platform.id.as_ref(),
+        ],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    #[account(
+        init_if_needed,
+        payer = publisher,
+        space = BatchData::DATA_OFFSET + args.size as usize,
+        seeds = [
+            BATCH_DATA_SEED_PREFIX,
+            platform.id.as_ref(),
+            publisher.key().as_ref(),
+            args.txs_hash.as_ref(),
+        ],
+        bump
+    )]
+    pub batch_data: Account<'info, BatchData>,
+    pub system_program: Program<'info, System>,
+}
+
+impl PublishBatchData<'_> {
+    pub fn handle(ctx: Context<Self>, args: PublishBatchDataArgs) -> Result<()> {
+        let batch_data = &mut ctx.accounts.batch_data;
+        if batch_data.bump != ctx.bumps.batch_data {
+            batch_data.platform = ctx.accounts.platform.id;
+            batch_data.publisher = ctx.accounts.publisher.key();
+            batch_data.txs_hash = args.txs_hash;
+            batch_data.size = args.size;
+            batch_data.bump = ctx.bumps.batch_data;
+        }
+
+        let offset = batch_data.written as usize;
+        if batch_data.sealed
+            || args.size != batch_data.size
+            || offset + args.data.len() > batch_data.size as usize
+        {
+            return Err(PlatformError::InvalidBatchData.into());
+        }
+
+        // The account is allocated for the whole data by the first chunk, the
+        // chunks are written in place, past the header
+        let info = batch_data.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        let start = BatchData::DATA_OFFSET + offset;
+        data[start..start + args.data.len()].copy_from_slice(&args.data);
+        batch_data.written += args.data.len() as u64;
+
+        if batch_data.written == batch_data.size {
+            if hashv(&[&data[BatchData::DATA_OFFSET..]]).to_bytes() != batch_data.txs_hash {
+                return Err(PlatformError::InvalidBatchData.into());
+            }
+            batch_data.sealed = true;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::constants::*;
+use crate::state::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SendMessageArgs {
+    pub program_id: Pubkey,
+    pub accounts: Vec<MessageAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Accounts)]
+#[instruction(args: SendMessageArgs)]
+pub struct SendMessage<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            PLATFORM_SEED_PREFIX,
+            platform.id.as_ref(),
+        ],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    #[account(
+        init,
+        payer = sender,
+        space = 8
+            + Message::INIT_SPACE
+            + args.accounts.len() * MessageAccountMeta::INIT_SPACE
+            + args.data.len(),
+        seeds = [
+            MESSAGE_SEED_PREFIX,
+            platform.id.as_ref(),
+            platform.message_tail.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub message: Account<'info, Message>,
+    pub system_program: Program<'info, System>,
+}
+
+impl SendMessage<'_> {
+    pub fn handle(ctx: Context<Self>, args: SendMessageArgs) -> Result<()> {
+        let platform = &mut ctx.accounts.platform;
+
+        let mut message = Message {
+            platform: platform.id,
+            index: platform.message_tail,
+            sender: ctx.accounts.sender.key(),
+            program_id: args.program_id,
+            accounts: args.accounts,
+            data: args.data,
+            hash: [0; 32],
+            bump: ctx.bumps.message,
+        };
+
+        let bytes = onchain_types::L1Message::from(&message).to_bytes();
+        platform.message_queue_hash = hashv(&[&platform.message_queue_hash, &bytes]).to_bytes();
+        platform.message_tail += 1;
+        message.hash = platform.message_queue_hash;
+
+        ctx.accounts.message.set_inner(message);
+
+        Ok(())
+    }
+}
@@ -2,12 +2,40 @@ use anchor_lang::prelude::*;
 
 use crate::constants::*;
 use crate::errors::PlatformError;
-use crate::state::platform::Platform;
-use crate::state::ramp::Ramp;
+use crate::state::*;
+use crate::utils::hashv_bytes;
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct WithdrawArgs {
+    pub batch_number: u64,
+    /// Position of the withdrawal in the batch
+    pub index: u64,
     pub amount: u64,
+    /// Merkle proof of the withdrawal against the withdrawals root of the batch
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl WithdrawArgs {
+    /// Checks that the batch released `amount` of `mint` to `user` at `index`.
+    pub fn verify(&self, batch: &Batch, user: &Pubkey, mint: Option<&Pubkey>) -> Result<()> {
+        let withdrawal = onchain_types::Withdrawal {
+            user: onchain_types::Pubkey(user.to_bytes()),
+            amount: self.amount,
+            mint: mint.map(|mint| onchain_types::Pubkey(mint.to_bytes())),
+        };
+        let leaf = withdrawal.leaf_hash(hashv_bytes, self.index);
+        if !onchain_types::merkle::verify(
+            hashv_bytes,
+            leaf,
+            self.index,
+            &self.proof,
+            &batch.withdrawals_root,
+        ) {
+            return Err(PlatformError::InvalidWithdrawalProof.into());
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -16,6 +44,7 @@ pub struct Withdraw<'info> {
     #[account(mut)]
     pub ramper: Signer<'info>,
     #[account(
+        mut,
         seeds = [
             PLATFORM_SEED_PREFIX,
             platform.id.as_ref(),
@@ -24,28 +53,46 @@ pub struct Withdraw<'info> {
     )]
     pub platform: Account<'info, Platform>,
     #[account(
-        mut,
         seeds = [
-            RAMP_SEED_PREFIX,
+            BATCH_SEED_PREFIX,
             platform.id.as_ref(),
-            ramper.key().as_ref(),
+            args.batch_number.to_le_bytes().as_ref(),
         ],
-        bump = ramp.bump
+        bump = batch.bump
     )]
-    pub ramp: Account<'info, Ramp>,
+    pub batch: Account<'info, Batch>,
+    /// Fails to be created again if the withdrawal was already claimed
+    #[account(
+        init,
+        payer = ramper,
+        space = 8 + WithdrawalClaim::INIT_SPACE,
+        seeds = [
+            WITHDRAWAL_CLAIM_SEED_PREFIX,
+            platform.id.as_ref(),
+            args.batch_number.to_le_bytes().as_ref(),
+            args.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub withdrawal_claim: Account<'info, WithdrawalClaim>,
+    pub system_program: Program<'info, System>,
 }
 
 impl Withdraw<'_> {
-    pub fn validate(&self) -> Result<()> {
-        if self.ramp.current_state_hash != self.platform.last_state_hash {
-            return Err(PlatformError::InvalidStateHash.into());
+    pub fn validate(&self, args: &WithdrawArgs) -> Result<()> {
+        args.verify(&self.batch, &self.ramper.key(), None)?;
+
+        if self.platform.withdraw + args.amount > self.platform.deposit {
+            return Err(PlatformError::InsufficientDeposits.into());
         }
 
         Ok(())
     }
 
     pub fn handle(ctx: Context<Self>, args: WithdrawArgs) -> Result<()> {
-        ctx.accounts.ramp.pending_withdraw -= args.amount;
+        ctx.accounts.withdrawal_claim.bump = ctx.bumps.withdrawal_claim;
+
+        ctx.accounts.platform.withdraw += args.amount;
         ctx.accounts.platform.sub_lamports(args.amount)?;
         ctx.accounts.ramper.add_lamports(args.amount)?;
 
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+
+use crate::constants::*;
+use crate::state::*;
+use crate::WithdrawArgs;
+
+#[derive(Accounts)]
+#[instruction(args: WithdrawArgs)]
+pub struct WithdrawToken<'info> {
+    #[account(mut)]
+    pub ramper: Signer<'info>,
+    #[account(
+        seeds = [
+            PLATFORM_SEED_PREFIX,
+            platform.id.as_ref(),
+        ],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    #[account(
+        seeds = [
+            BATCH_SEED_PREFIX,
+            platform.id.as_ref(),
+            args.batch_number.to_le_bytes().as_ref(),
+        ],
+        bump = batch.bump
+    )]
+    pub batch: Account<'info, Batch>,
+    /// Fails to be created again if the withdrawal was already claimed
+    #[account(
+        init,
+        payer = ramper,
+        space = 8 + WithdrawalClaim::INIT_SPACE,
+        seeds = [
+            WITHDRAWAL_CLAIM_SEED_PREFIX,
+            platform.id.as_ref(),
+            args.batch_number.to_le_bytes().as_ref(),
+            args.index.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub withdrawal_claim: Account<'info, WithdrawalClaim>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = platform,
+        seeds = [
+            VAULT_SEED_PREFIX,
+            platform.id.as_ref(),
+            mint.key().as_ref(),
+        ],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = mint,
+    )]
+    pub ramper_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl WithdrawToken<'_> {
+    pub fn validate(&self, args: &WithdrawArgs) -> Result<()> {
+        args.verify(&self.batch, &self.ramper.key(), Some(&self.mint.key()))
+    }
+
+    pub fn handle(ctx: Context<Self>, args: WithdrawArgs) -> Result<()> {
+        ctx.accounts.withdrawal_claim.bump = ctx.bumps.withdrawal_claim;
+
+        let platform = &ctx.accounts.platform;
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.ramper_token_account.to_account_info(),
+                    authority: platform.to_account_info(),
+                },
+                &[&[PLATFORM_SEED_PREFIX, platform.id.as_ref(), &[platform.bump]]],
+            ),
+            args.amount,
+        )?;
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ pub mod constants;
 pub mod errors;
 pub mod instructions;
 pub mod state;
+pub mod utils;
 
 use anchor_lang::prelude::*;
 
@@ -9,6 +10,9 @@ pub use constants::*;
 pub use instructions::*;
 pub use state::*;
 
+#[cfg(all(feature = "mock-proofs", not(feature = "localnet")))]
+compile_error!("`mock-proofs` accepts unproven batches, it only builds for a local validator with the `localnet` feature");
+
 declare_id!("Bq5sTpeHWMCety13tmZqgYvDJoAAC4mAwBn33mZmuc41");
 
 #[program]
@@ -21,21 +25,69 @@ pub mod zk_bridge {
 
     /// Add a ramp transaction to the platform.
     ///
-    /// **This can currently be used to DoS the platform by adding transactions faster than the sequencer can generate proofs.**
+    /// Ramp transactions are queued in pages of `RAMP_PAGE_CAPACITY` entries, and each proof
+    /// consumes a prefix of the queue, so a burst of ramps only delays the ones behind it.
     pub fn add_ramp_tx(ctx: Context<AddRampTx>, args: AddRampTxArgs) -> Result<()> {
         AddRampTx::handle(ctx, args)
     }
 
-    pub fn upload_commit(ctx: Context<UploadCommit>, args: UploadCommitArgs) -> Result<()> {
-        UploadCommit::handle(ctx, args)
+    /// Add a token ramp transaction, locking or releasing tokens in the platform vault of the mint.
+    pub fn add_token_ramp_tx(ctx: Context<AddTokenRampTx>, args: AddTokenRampTxArgs) -> Result<()> {
+        AddTokenRampTx::handle(ctx, args)
+    }
+
+    /// Queue an instruction to be executed on the rollup at the start of the next batch.
+    ///
+    /// On L2 the sender signs through its alias, derived from `["l1_sender", sender]`. A message
+    /// asking for any other signer, or listing the L2 bridge accounts, is skipped by the rollup.
+    pub fn send_message(ctx: Context<SendMessage>, args: SendMessageArgs) -> Result<()> {
+        SendMessage::handle(ctx, args)
+    }
+
+    /// Publish the transactions of a batch, in chunks written in order to a `BatchData` account.
+    ///
+    /// The first chunk allocates the account for `size` bytes, whose rent the publisher pays. The
+    /// account is sealed once they are all written and hash to `txs_hash`.
+    pub fn publish_batch_data(
+        ctx: Context<PublishBatchData>,
+        args: PublishBatchDataArgs,
+    ) -> Result<()> {
+        PublishBatchData::handle(ctx, args)
     }
 
-    pub fn prove(ctx: Context<Prove>, proof: Vec<u8>) -> Result<()> {
-        Prove::handle(ctx, proof)
+    /// Close a `BatchData` account that was never sealed, refunding its rent to the publisher.
+    ///
+    /// Sealed batch data is kept for good, as it is what the rollup state is rebuilt from.
+    pub fn close_batch_data(ctx: Context<CloseBatchData>) -> Result<()> {
+        CloseBatchData::handle(ctx)
     }
 
-    #[access_control(ctx.accounts.validate())]
+    /// Verify a batch proof and its public values, and advance the platform to the new state.
+    pub fn prove(ctx: Context<Prove>, args: ProveArgs) -> Result<()> {
+        Prove::handle(ctx, args)
+    }
+
+    /// Execute a message written to the L2 outbox of a proven batch.
+    ///
+    /// The accounts of the message instruction are passed as remaining accounts, after the
+    /// program it invokes. The L2 sender signs through its alias, derived from
+    /// `["l2_sender", platform.id, sender]`.
+    pub fn execute_l2_message<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteL2Message<'info>>,
+        args: ExecuteL2MessageArgs,
+    ) -> Result<()> {
+        ExecuteL2Message::handle(ctx, args)
+    }
+
+    /// Claim native SOL released by a proven batch.
+    #[access_control(ctx.accounts.validate(&args))]
     pub fn withdraw(ctx: Context<Withdraw>, args: WithdrawArgs) -> Result<()> {
         Withdraw::handle(ctx, args)
     }
+
+    /// Claim tokens released by a proven batch from the platform vault of the mint.
+    #[access_control(ctx.accounts.validate(&args))]
+    pub fn withdraw_token(ctx: Context<WithdrawToken>, args: WithdrawArgs) -> Result<()> {
+        WithdrawToken::handle(ctx, args)
+    }
 }
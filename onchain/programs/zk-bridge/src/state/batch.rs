@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+/// A proven batch, kept to execute the L2 messages it wrote
+#[account]
+#[derive(InitSpace)]
+pub struct Batch {
+    pub number: u64,
+    pub state_hash: [u8; 32],
+    /// Hash of the transactions of the batch
+    pub txs_hash: [u8; 32],
+    /// Merkle root of the L2 outbox of the batch
+    pub outbox_root: [u8; 32],
+    /// Merkle root of the withdrawals released by the batch
+    pub withdrawals_root: [u8; 32],
+    /// Ramp queue head after the batch, the batch consumed the ramp txs from
+    /// the head of the previous batch up to this one
+    pub ramp_head: u64,
+    /// L1 message queue head after the batch
+    pub message_head: u64,
+    /// `BatchData` account holding the transactions of the batch
+    pub batch_data: Pubkey,
+    pub bump: u8,
+}
+
+/// Marks a withdrawal as claimed
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalClaim {
+    pub bump: u8,
+}
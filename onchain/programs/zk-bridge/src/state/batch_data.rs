@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+/// Transactions of a batch published on L1, so the rollup state can be rebuilt
+/// without the sequencer.
+///
+/// Only this header is deserialized, the bincode encoded transactions follow it
+/// as raw bytes at [`BatchData::DATA_OFFSET`], so batches are not bounded by
+/// the heap.
+///
+/// Once sealed the account is never closed, its rent pays for that
+/// availability. An upload abandoned before being sealed can be closed by its
+/// publisher with `close_batch_data`.
+#[account]
+#[derive(InitSpace)]
+pub struct BatchData {
+    /// Id of the platform
+    pub platform: Pubkey,
+    pub publisher: Pubkey,
+    /// Hash of the data, the `txs_hash` committed by the proof of the batch
+    pub txs_hash: [u8; 32],
+    /// Length of the complete data
+    pub size: u64,
+    /// Length of the data written so far
+    pub written: u64,
+    /// Set once all the data is written and matches `txs_hash`
+    pub sealed: bool,
+    pub bump: u8,
+}
+
+impl BatchData {
+    /// Offset of the data in the account, after the discriminator and the header
+    pub const DATA_OFFSET: usize = 8 + Self::INIT_SPACE;
+}
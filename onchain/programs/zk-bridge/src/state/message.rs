@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct MessageAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An L1 to L2 message waiting to be executed by the rollup
+#[account]
+#[derive(InitSpace)]
+pub struct Message {
+    /// Id of the platform
+    pub platform: Pubkey,
+    pub index: u64,
+    pub sender: Pubkey,
+    pub program_id: Pubkey,
+    #[max_len(0)]
+    pub accounts: Vec<MessageAccountMeta>,
+    #[max_len(0)]
+    pub data: Vec<u8>,
+    /// Message queue hash up to and including this message
+    pub hash: [u8; 32],
+    pub bump: u8,
+}
+
+/// Marks an L2 message as executed on L1
+#[account]
+#[derive(InitSpace)]
+pub struct ExecutedMessage {
+    pub bump: u8,
+}
+
+impl From<&MessageAccountMeta> for onchain_types::AccountMeta {
+    fn from(meta: &MessageAccountMeta) -> Self {
+        onchain_types::AccountMeta {
+            pubkey: onchain_types::Pubkey(meta.pubkey.to_bytes()),
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }
+    }
+}
+
+impl From<&Message> for onchain_types::L1Message {
+    fn from(message: &Message) -> Self {
+        onchain_types::L1Message {
+            sender: onchain_types::Pubkey(message.sender.to_bytes()),
+            program_id: onchain_types::Pubkey(message.program_id.to_bytes()),
+            accounts: message.accounts.iter().map(Into::into).collect(),
+            data: message.data.clone(),
+        }
+    }
+}
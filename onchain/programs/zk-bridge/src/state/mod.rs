@@ -1,7 +1,11 @@
-pub mod commit;
+pub mod batch;
+pub mod batch_data;
+pub mod message;
 pub mod platform;
-pub mod ramp;
+pub mod ramp_page;
 
-pub use commit::*;
+pub use batch::*;
+pub use batch_data::*;
+pub use message::*;
 pub use platform::*;
-pub use ramp::*;
+pub use ramp_page::*;
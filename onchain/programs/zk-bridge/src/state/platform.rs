@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use super::{RampEntry, RampPage};
+use crate::constants::RAMP_PAGE_CAPACITY;
 
 /// A platform is the account storing state waiting to be sent to the rollup
 #[account]
@@ -7,16 +11,76 @@ pub struct Platform {
     pub sequencer: Pubkey,
     pub id: Pubkey,
     pub last_state_hash: [u8; 32],
-    #[max_len(0)]
-    pub ramp_txs: Vec<RampTx>,
+    /// Hash identifying the rollup configuration, proofs must commit to it
+    pub config_hash: [u8; 32],
+    /// Index of the first ramp tx not executed by the rollup yet
+    pub ramp_head: u64,
+    /// Index of the next ramp tx
+    pub ramp_tail: u64,
+    /// Ramp txs hash of the queue before `ramp_head`
+    pub ramp_head_hash: [u8; 32],
+    /// Ramp txs hash of the queue before `ramp_tail`
+    pub ramp_txs_hash: [u8; 32],
     pub deposit: u64,
     pub withdraw: u64,
+    /// Index of the first L1 message not executed by the rollup yet
+    pub message_head: u64,
+    /// Index of the next L1 message
+    pub message_tail: u64,
+    /// Message queue hash of the queue before `message_head`
+    pub message_head_hash: [u8; 32],
+    /// Message queue hash of the queue before `message_tail`
+    pub message_queue_hash: [u8; 32],
+    /// Number of proven batches
+    pub batch_number: u64,
     pub bump: u8,
 }
 
+impl Platform {
+    /// Page the next ramp tx is written to
+    pub fn tail_page_index(&self) -> u64 {
+        self.ramp_tail / RAMP_PAGE_CAPACITY
+    }
+
+    /// Queues a ramp tx in `page`, the tail page, and folds it into the ramp txs hash.
+    pub fn push_ramp_tx(&mut self, page: &mut RampPage, page_bump: u8, ramp_tx: RampTx) {
+        if page.entries.is_empty() {
+            page.platform = self.id;
+            page.index = self.tail_page_index();
+            page.bump = page_bump;
+        }
+
+        let bytes = onchain_types::RampTx::from(&ramp_tx).to_bytes();
+        self.ramp_txs_hash = hashv(&[&self.ramp_txs_hash, &bytes]).to_bytes();
+        self.ramp_tail += 1;
+
+        page.entries.push(RampEntry {
+            ramp_tx,
+            hash: self.ramp_txs_hash,
+        });
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct RampTx {
     pub is_onramp: bool,
     pub user: Pubkey,
     pub amount: u64,
+    /// Mint of a token ramp, `None` for native SOL
+    pub mint: Option<Pubkey>,
+    pub decimals: u8,
+}
+
+impl From<&RampTx> for onchain_types::RampTx {
+    fn from(ramp_tx: &RampTx) -> Self {
+        onchain_types::RampTx {
+            is_onramp: ramp_tx.is_onramp,
+            user: onchain_types::Pubkey(ramp_tx.user.to_bytes()),
+            amount: ramp_tx.amount,
+            mint: ramp_tx
+                .mint
+                .map(|mint| onchain_types::Pubkey(mint.to_bytes())),
+            decimals: ramp_tx.decimals,
+        }
+    }
 }
@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use super::RampTx;
+use crate::constants::RAMP_PAGE_CAPACITY;
+
+/// A page of the ramp queue, entry `i` of the queue is in page `i / RAMP_PAGE_CAPACITY`
+///
+/// Pages are never closed, even once consumed, as they are where the ramp txs
+/// of every batch are read from to rebuild the rollup from L1.
+#[account]
+#[derive(InitSpace)]
+pub struct RampPage {
+    /// Id of the platform
+    pub platform: Pubkey,
+    pub index: u64,
+    #[max_len(RAMP_PAGE_CAPACITY as usize)]
+    pub entries: Vec<RampEntry>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RampEntry {
+    pub ramp_tx: RampTx,
+    /// Ramp txs hash of the queue up to and including this entry
+    pub hash: [u8; 32],
+}
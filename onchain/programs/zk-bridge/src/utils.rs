@@ -0,0 +1,6 @@
+use anchor_lang::solana_program::hash::hashv;
+
+/// `hashv` returning bytes, the hash function of the `onchain_types::merkle` proofs
+pub fn hashv_bytes(vals: &[&[u8]]) -> [u8; 32] {
+    hashv(vals).to_bytes()
+}
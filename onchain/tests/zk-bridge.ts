@@ -1,9 +1,11 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
 import { ZkBridge } from "../target/types/zk_bridge";
 import kpSender from "./keypairSender.json";
 import kpReceiver from "./keypairReceiver.json";
 import * as fs from "fs";
+import { createHash } from "crypto";
 import * as borsh from "borsh";
 
 // Define the structure of OnChainProof in TypeScript
@@ -26,7 +28,12 @@ describe("zk-bridge", () => {
 
   const program = anchor.workspace.ZkBridge as Program<ZkBridge>;
 
-  const initialStateHash = "EukGGeg2sN2tETkZQP4kPTQxJQU859P8j5JGNLBKSt87";
+  // Generated by `anchor run fixtures`, which `anchor test` runs first
+  const genesisRoot = JSON.parse(
+    fs.readFileSync("../script/genesis-root.json", "utf8")
+  );
+  const initialStateHash: number[] = genesisRoot.rootBytes;
+  const configHash: number[] = genesisRoot.configHashBytes;
   const senderKeypair = anchor.web3.Keypair.fromSecretKey(
     Uint8Array.from(Buffer.from(kpSender))
   );
@@ -35,14 +42,21 @@ describe("zk-bridge", () => {
   );
 
   const PLATFORM_SEED_PREFIX = getConstant(program.idl, "platformSeedPrefix");
-  const COMMIT_SEED_PREFIX = getConstant(program.idl, "commitSeedPrefix");
-  const RAMP_SEED_PREFIX = getConstant(program.idl, "rampSeedPrefix");
+  const BATCH_SEED_PREFIX = getConstant(program.idl, "batchSeedPrefix");
+  const RAMP_PAGE_SEED_PREFIX = getConstant(program.idl, "rampPageSeedPrefix");
+  const BATCH_DATA_SEED_PREFIX = getConstant(
+    program.idl,
+    "batchDataSeedPrefix"
+  );
 
-  // Read the file containing the serialized data
-  let filePath = "../script/onchain-commit.bin";
-  const commitData = Uint8Array.from(fs.readFileSync(filePath));
+  // Generated by `anchor run fixtures` as well
+  let filePath = "../script/onchain-public-values.bin";
+  const publicValues = Uint8Array.from(fs.readFileSync(filePath));
   filePath = "../script/onchain-proof.bin";
   const proofData = Uint8Array.from(fs.readFileSync(filePath));
+  filePath = "../script/batch-data.bin";
+  const batchData = fs.readFileSync(filePath);
+  const txsHash = Array.from(createHash("sha256").update(batchData).digest());
 
   // console.log("Commit Data Length:", commitData.length);
   // console.log("Commit Data:", commitData);
@@ -67,14 +81,16 @@ describe("zk-bridge", () => {
         [Buffer.from(PLATFORM_SEED_PREFIX), platformId.toBuffer()],
         program.programId
       );
-    const [rampKey, _rampBump] = anchor.web3.PublicKey.findProgramAddressSync(
-      [
-        Buffer.from(RAMP_SEED_PREFIX),
-        platformId.toBuffer(),
-        senderKeypair.publicKey.toBuffer(),
-      ],
-      program.programId
-    );
+
+    const [rampPageKey, _rampPageBump] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(RAMP_PAGE_SEED_PREFIX),
+          platformId.toBuffer(),
+          new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
 
     await provider.connection.confirmTransaction(
       await provider.connection.requestAirdrop(
@@ -86,7 +102,8 @@ describe("zk-bridge", () => {
     await program.methods
       .createPlatform({
         id: platformId,
-        initialStateHash: Array.from(Buffer.from(initialStateHash)),
+        initialStateHash,
+        configHash,
       })
       .accountsPartial({
         sequencer: senderKeypair.publicKey,
@@ -103,56 +120,67 @@ describe("zk-bridge", () => {
       })
       .accountsPartial({
         ramper: senderKeypair.publicKey,
-        ramp: rampKey,
+        rampPage: rampPageKey,
         platform: platformKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([senderKeypair])
       .rpc();
 
-    // Upload commit
-    const [commitKey, _commitBump] =
+    const [batchDataKey, _batchDataBump] =
       anchor.web3.PublicKey.findProgramAddressSync(
         [
-          Buffer.from(COMMIT_SEED_PREFIX),
+          Buffer.from(BATCH_DATA_SEED_PREFIX),
           platformId.toBuffer(),
           senderKeypair.publicKey.toBuffer(),
+          Buffer.from(txsHash),
         ],
         program.programId
       );
 
-    let dataLeft = commitData;
-
-    let offset = 0;
-    while (dataLeft.length > 0) {
-      console.log(`uploading`);
-      const size = Math.min(dataLeft.length, 800);
+    // The transactions of the batch must be on L1 before it can be proven
+    const chunkSize = 900;
+    for (let offset = 0; offset < batchData.length; offset += chunkSize) {
       await program.methods
-        .uploadCommit({
-          commitSize: new anchor.BN(commitData.length),
-          offset: new anchor.BN(offset),
-          commitData: Buffer.from(dataLeft.subarray(0, size)),
+        .publishBatchData({
+          txsHash,
+          size: new anchor.BN(batchData.length),
+          data: batchData.subarray(offset, offset + chunkSize),
         })
         .accountsPartial({
-          prover: senderKeypair.publicKey,
-          commit: commitKey,
+          publisher: senderKeypair.publicKey,
           platform: platformKey,
+          batchData: batchDataKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
         })
         .signers([senderKeypair])
         .rpc();
-
-      dataLeft = dataLeft.subarray(size);
-      offset += size;
     }
 
     console.log(`proving`);
 
+    const [batchKey, _batchBump] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(BATCH_SEED_PREFIX),
+          platformId.toBuffer(),
+          new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
     await program.methods
-      .prove(Buffer.from(proofData))
+      .prove({
+        proof: Buffer.from(proofData),
+        publicValues: Buffer.from(publicValues),
+      })
       .accountsPartial({
         prover: senderKeypair.publicKey,
-        commit: commitKey,
         platform: platformKey,
+        batch: batchKey,
+        batchData: batchDataKey,
+        rampPage: rampPageKey,
+        lastMessage: null,
       })
       .preInstructions([
         anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
@@ -161,6 +189,65 @@ describe("zk-bridge", () => {
       ])
       .signers([senderKeypair])
       .rpc();
+
+    // Replaying the same proof must fail, it starts from the previous state
+    const [nextBatchKey, _nextBatchBump] =
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from(BATCH_SEED_PREFIX),
+          platformId.toBuffer(),
+          new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+    try {
+      await program.methods
+        .prove({
+          proof: Buffer.from(proofData),
+          publicValues: Buffer.from(publicValues),
+        })
+        .accountsPartial({
+          prover: senderKeypair.publicKey,
+          platform: platformKey,
+          batch: nextBatchKey,
+          batchData: batchDataKey,
+          rampPage: rampPageKey,
+          lastMessage: null,
+        })
+        .preInstructions([
+          anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+            units: 1_400_000,
+          }),
+        ])
+        .signers([senderKeypair])
+        .rpc();
+      expect.fail("stale proof was accepted");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "InvalidInputStateHash"
+      );
+    }
+
+    // The data of the proven batch is kept for good
+    try {
+      await program.methods
+        .closeBatchData()
+        .accountsPartial({
+          publisher: senderKeypair.publicKey,
+          platform: platformKey,
+          batchData: batchDataKey,
+        })
+        .signers([senderKeypair])
+        .rpc();
+      expect.fail("sealed batch data was closed");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "InvalidBatchData"
+      );
+    }
   });
 });
 
@@ -0,0 +1,11 @@
+use sp1_build::BuildArgs;
+
+fn main() {
+    sp1_build::build_program_with_args(
+        "../program",
+        BuildArgs {
+            docker: true,
+            ..Default::default()
+        },
+    );
+}
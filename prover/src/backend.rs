@@ -0,0 +1,94 @@
+use bridge_client::{verify_onchain_proof, ProveArgs};
+use clap::ValueEnum;
+use onchain_types::ZK_BRIDGE_VKEY_HASH;
+use sp1_sdk::{
+    include_elf, CpuProver, HashableKey, Prover, ProverClient, SP1ProofWithPublicValues,
+    SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+use svm_runner_types::ExecutionInput;
+
+pub const ZK_SVM_ELF: &[u8] = include_elf!("zk-svm-program");
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Executes the program and returns a Groth16 proof that does not verify,
+    /// for testing the pipeline
+    Mock,
+    /// Compressed STARK proof generated locally, not verifiable on L1
+    Cpu,
+    /// Compressed proof wrapped in Groth16 locally, verifiable by zk-bridge
+    Groth16,
+}
+
+impl Backend {
+    /// Whether zk-bridge `prove` can be called with the proofs of the backend
+    pub fn is_onchain(&self) -> bool {
+        *self != Backend::Cpu
+    }
+}
+
+/// SP1 prover set up for the zk-svm program.
+pub struct BatchProver {
+    backend: Backend,
+    client: CpuProver,
+    pk: SP1ProvingKey,
+    vk: SP1VerifyingKey,
+}
+
+impl BatchProver {
+    pub fn new(backend: Backend) -> Self {
+        let client = match backend {
+            Backend::Mock => ProverClient::builder().mock().build(),
+            Backend::Cpu | Backend::Groth16 => ProverClient::builder().cpu().build(),
+        };
+        let (pk, vk) = client.setup(ZK_SVM_ELF);
+        println!("Verifying key: {}", vk.bytes32());
+        if backend == Backend::Groth16 && vk.bytes32() != ZK_BRIDGE_VKEY_HASH {
+            eprintln!(
+                "Warning: zk-bridge only accepts proofs of verifying key {}",
+                ZK_BRIDGE_VKEY_HASH
+            );
+        }
+        BatchProver {
+            backend,
+            client,
+            pk,
+            vk,
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+
+    pub fn prove(&self, input: &ExecutionInput) -> Result<SP1ProofWithPublicValues, String> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(input);
+
+        let builder = self.client.prove(&self.pk, &stdin);
+        match self.backend {
+            Backend::Cpu => builder.compressed().run(),
+            Backend::Mock | Backend::Groth16 => builder.groth16().run(),
+        }
+        .map_err(|e| e.to_string())
+    }
+
+    /// Checks a proof of the backend, Groth16 proofs also the way zk-bridge
+    /// `prove` does. Mock proofs are not checked.
+    pub fn verify(&self, proof: &SP1ProofWithPublicValues) -> Result<(), String> {
+        if self.backend == Backend::Mock {
+            return Ok(());
+        }
+        self.client
+            .verify(proof, &self.vk)
+            .map_err(|e| e.to_string())?;
+        if self.backend == Backend::Groth16 {
+            let args = ProveArgs {
+                proof: proof.bytes(),
+                public_values: proof.public_values.to_vec(),
+            };
+            verify_onchain_proof(&args).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
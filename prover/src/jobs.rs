@@ -0,0 +1,62 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use svm_runner_types::BatchJob;
+
+/// Files of the jobs directory shared with the sequencer.
+pub struct JobFiles {
+    dir: PathBuf,
+}
+
+impl JobFiles {
+    pub fn new(dir: &Path) -> Self {
+        JobFiles {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Bincode [`BatchJob`], written by the sequencer
+    pub fn input_path(&self, batch_number: u64) -> PathBuf {
+        self.dir.join(format!("batch-{}.bin", batch_number))
+    }
+
+    /// `SP1ProofWithPublicValues` saved by SP1, written last so the sequencer
+    /// can take its existence as the batch being proven
+    pub fn proof_path(&self, batch_number: u64) -> PathBuf {
+        self.dir.join(format!("batch-{}.proof.bin", batch_number))
+    }
+
+    /// Public values, the `public_values` argument of zk-bridge `prove`
+    pub fn public_values_path(&self, batch_number: u64) -> PathBuf {
+        self.dir
+            .join(format!("batch-{}.public-values.bin", batch_number))
+    }
+
+    /// Groth16 proof, the `proof` argument of zk-bridge `prove`
+    pub fn onchain_proof_path(&self, batch_number: u64) -> PathBuf {
+        self.dir
+            .join(format!("batch-{}.onchain-proof.bin", batch_number))
+    }
+
+    pub fn read_job(&self, batch_number: u64) -> io::Result<BatchJob> {
+        let bytes = fs::read(self.input_path(batch_number))?;
+        bincode::deserialize(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Batch numbers of the inputs in the directory, in order
+    pub fn batch_numbers(&self) -> io::Result<Vec<u64>> {
+        let mut batch_numbers = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let number = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("batch-"))
+                .and_then(|name| name.strip_suffix(".bin"))
+                .and_then(|number| number.parse().ok());
+            batch_numbers.extend(number);
+        }
+        batch_numbers.sort_unstable();
+        Ok(batch_numbers)
+    }
+}
@@ -0,0 +1,226 @@
+use clap::Parser;
+use onchain_types::PublicValues;
+use rollup_storage::write_atomic;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::read_keypair_file};
+use std::{
+    error::Error,
+    fs,
+    path::PathBuf,
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+use svm_runner_types::hash_state;
+
+use crate::{
+    backend::{Backend, BatchProver},
+    jobs::JobFiles,
+    queue::{Job, JobQueue, JobStatus},
+    submitter::Submitter,
+};
+
+mod backend;
+mod jobs;
+mod queue;
+mod submitter;
+
+/// How often the jobs directory is checked for new batches
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Rollup prover.
+///
+/// Proves the batch jobs the sequencer writes to its jobs directory, in order,
+/// and writes the proof artifacts next to them. With a keypair, publishes the
+/// batch data of each proven batch and submits its proof to zk-bridge.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Jobs directory of the sequencer
+    #[clap(long, default_value = "../sequencer/sequencer-data/jobs")]
+    jobs_dir: PathBuf,
+
+    /// Database of the job queue
+    #[clap(long, default_value = "./prover.redb")]
+    queue_path: PathBuf,
+
+    #[clap(long, value_enum, default_value_t = Backend::Groth16)]
+    backend: Backend,
+
+    /// Attempts at proving a batch before giving up until the next restart
+    #[clap(long, default_value_t = 3)]
+    max_attempts: u32,
+
+    #[clap(long, default_value_t = 10)]
+    retry_delay_secs: u64,
+
+    /// Keypair submitting the proofs, they are only written without it
+    #[clap(long, requires = "platform_id")]
+    keypair: Option<PathBuf>,
+
+    #[clap(long)]
+    platform_id: Option<Pubkey>,
+
+    #[clap(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let files = Arc::new(JobFiles::new(&args.jobs_dir));
+    let queue = JobQueue::open(&args.queue_path).unwrap_or_else(|e| {
+        eprintln!("Error: {}: {}", args.queue_path.display(), e);
+        std::process::exit(1);
+    });
+    let queue = Arc::new(queue);
+    queue.reset_failed().unwrap();
+
+    let submitter = args.keypair.map(|keypair| {
+        if !args.backend.is_onchain() {
+            eprintln!("Error: {:?} proofs cannot be submitted", args.backend);
+            std::process::exit(1);
+        }
+        let payer = read_keypair_file(&keypair).unwrap_or_else(|e| {
+            eprintln!("Error: {}: {}", keypair.display(), e);
+            std::process::exit(1);
+        });
+        let submitter = Submitter::new(
+            RpcClient::new(args.rpc_url),
+            payer,
+            args.platform_id.unwrap(),
+            queue.clone(),
+            files.clone(),
+        );
+
+        let (proven, receiver) = mpsc::channel();
+        thread::spawn(move || submitter.run(receiver));
+        // Proven before a restart
+        for job in queue.jobs().unwrap() {
+            if job.status == JobStatus::Proven {
+                proven.send(job.batch_number).unwrap();
+            }
+        }
+        proven
+    });
+
+    let prover = BatchProver::new(args.backend);
+    loop {
+        if let Err(e) = enqueue_new_jobs(&queue, &files) {
+            eprintln!("Error: {}", e);
+        }
+
+        let Some(mut job) = queue.next_to_prove().unwrap() else {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+        println!("Proving batch {}", job.batch_number);
+        match prove_job(&prover, &queue, &files, &job) {
+            Ok(post_state_hash) => {
+                println!("Batch {} proven: {}", job.batch_number, post_state_hash);
+                job.post_state_hash = Some(post_state_hash);
+                job.status = JobStatus::Proven;
+                queue.update(&job).unwrap();
+                if let Some(proven) = &submitter {
+                    proven.send(job.batch_number).unwrap();
+                }
+            }
+            Err(JobError::Invalid(e)) => {
+                eprintln!("Error: batch {} cannot be proven: {}", job.batch_number, e);
+                job.status = JobStatus::Failed(e);
+                queue.update(&job).unwrap();
+            }
+            Err(JobError::Failed(e)) => {
+                job.attempts += 1;
+                eprintln!(
+                    "Error: batch {}, attempt {}/{}: {}",
+                    job.batch_number, job.attempts, args.max_attempts, e
+                );
+                if job.attempts >= args.max_attempts {
+                    job.status = JobStatus::Failed(e);
+                }
+                queue.update(&job).unwrap();
+                thread::sleep(Duration::from_secs(args.retry_delay_secs));
+            }
+        }
+    }
+}
+
+enum JobError {
+    /// The job can never be proven
+    Invalid(String),
+    /// Proving failed, it may succeed again
+    Failed(String),
+}
+
+/// Queues the jobs the sequencer wrote since the last call, with the state
+/// hash the sequencer committed before each batch as the state its proof must
+/// start from.
+fn enqueue_new_jobs(queue: &JobQueue, files: &JobFiles) -> Result<(), Box<dyn Error>> {
+    let last = queue.last()?.map(|job| job.batch_number);
+    for batch_number in files.batch_numbers()? {
+        if last.is_some_and(|last| batch_number <= last) {
+            continue;
+        }
+        let job = files.read_job(batch_number)?;
+        queue.enqueue(batch_number, job.pre_state_hash)?;
+    }
+    Ok(())
+}
+
+/// Proves a batch and writes its artifacts, returns its post-state hash.
+fn prove_job(
+    prover: &BatchProver,
+    queue: &JobQueue,
+    files: &JobFiles,
+    job: &Job,
+) -> Result<Hash, JobError> {
+    // Proofs chain on L1, so the batch must start where the previous one ends
+    let previous = job.batch_number.checked_sub(1);
+    if let Some(previous) = previous.and_then(|previous| queue.get(previous).unwrap()) {
+        if previous.post_state_hash != Some(job.pre_state_hash) {
+            return Err(JobError::Invalid(format!(
+                "pre-state hash {} is not the post-state hash of batch {}",
+                job.pre_state_hash, previous.batch_number
+            )));
+        }
+    }
+
+    let input = files
+        .read_job(job.batch_number)
+        .map_err(|e| JobError::Failed(e.to_string()))?
+        .input;
+    // The proof starts from the state of the input
+    if hash_state(&input.accounts) != job.pre_state_hash {
+        return Err(JobError::Invalid(format!(
+            "input state hash {} is not the pre-state hash {}",
+            hash_state(&input.accounts),
+            job.pre_state_hash
+        )));
+    }
+    let proof = prover.prove(&input).map_err(JobError::Failed)?;
+    // The proof of the same input would not verify either
+    prover.verify(&proof).map_err(JobError::Invalid)?;
+
+    let public_values = proof.public_values.to_vec();
+    let values = PublicValues::from_bytes(&public_values)
+        .ok_or_else(|| JobError::Invalid("invalid public values".to_string()))?;
+
+    let write = |path: PathBuf, data: &[u8]| {
+        write_atomic(&path, data)
+            .map_err(|e| JobError::Failed(format!("{}: {}", path.display(), e)))
+    };
+    write(files.public_values_path(job.batch_number), &public_values)?;
+    if prover.backend().is_onchain() {
+        write(files.onchain_proof_path(job.batch_number), &proof.bytes())?;
+    }
+    // Last, the sequencer confirms the batch once it exists
+    let path = files.proof_path(job.batch_number);
+    let tmp = path.with_extension("tmp");
+    proof
+        .save(&tmp)
+        .map_err(|e| JobError::Failed(e.to_string()))?;
+    fs::rename(&tmp, &path).map_err(|e| JobError::Failed(e.to_string()))?;
+
+    Ok(Hash::new_from_array(values.post_state_hash))
+}
@@ -0,0 +1,180 @@
+use redb::{Database, ReadableTable, TableDefinition};
+use rollup_storage::{create_database, impl_from_redb_error};
+use serde::{Deserialize, Serialize};
+use solana_sdk::hash::Hash;
+use std::{fmt, path::Path};
+
+/// Batch number to bincode [`Job`]
+const JOBS: TableDefinition<u64, &[u8]> = TableDefinition::new("jobs");
+
+#[derive(Debug)]
+pub enum QueueError {
+    Database(redb::Error),
+    Corrupted(String),
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueError::Database(err) => write!(f, "job queue error: {}", err),
+            QueueError::Corrupted(err) => write!(f, "corrupted job queue: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+impl_from_redb_error!(QueueError);
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum JobStatus {
+    /// Waiting to be proven, possibly again after a failed attempt
+    Pending,
+    /// Out of attempts, or its input can never be proven
+    Failed(String),
+    /// Proof artifacts are written, waiting to be submitted
+    Proven,
+    /// Accepted by zk-bridge `prove`
+    Submitted,
+}
+
+/// Proof of a batch.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    pub batch_number: u64,
+    /// State hash the batch starts from, which the proof must commit to
+    pub pre_state_hash: Hash,
+    /// State hash after the batch, once proven
+    pub post_state_hash: Option<Hash>,
+    /// Failed proving attempts
+    pub attempts: u32,
+    pub status: JobStatus,
+}
+
+/// Jobs of the prover, kept across restarts. Batches are proven in order,
+/// each starting from the state the previous one ended with.
+pub struct JobQueue {
+    db: Database,
+}
+
+impl JobQueue {
+    pub fn open(path: &Path) -> Result<Self, QueueError> {
+        let db = create_database(path, |txn| {
+            txn.open_table(JOBS)?;
+            Ok(())
+        })?;
+        Ok(JobQueue { db })
+    }
+
+    /// Queues the proof of a batch, unless it is queued already.
+    pub fn enqueue(&self, batch_number: u64, pre_state_hash: Hash) -> Result<(), QueueError> {
+        if self.get(batch_number)?.is_some() {
+            return Ok(());
+        }
+        self.update(&Job {
+            batch_number,
+            pre_state_hash,
+            post_state_hash: None,
+            attempts: 0,
+            status: JobStatus::Pending,
+        })
+    }
+
+    pub fn get(&self, batch_number: u64) -> Result<Option<Job>, QueueError> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(JOBS)?;
+        let job = table.get(batch_number)?;
+        job.map(|job| deserialize(job.value())).transpose()
+    }
+
+    pub fn update(&self, job: &Job) -> Result<(), QueueError> {
+        let txn = self.db.begin_write()?;
+        txn.open_table(JOBS)?.insert(
+            job.batch_number,
+            bincode::serialize(job).unwrap().as_slice(),
+        )?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    pub fn jobs(&self) -> Result<Vec<Job>, QueueError> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(JOBS)?;
+        let mut jobs = Vec::new();
+        for entry in table.iter()? {
+            let (_, job) = entry?;
+            jobs.push(deserialize(job.value())?);
+        }
+        Ok(jobs)
+    }
+
+    pub fn last(&self) -> Result<Option<Job>, QueueError> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(JOBS)?;
+        let last = table.last()?;
+        last.map(|(_, job)| deserialize(job.value())).transpose()
+    }
+
+    /// First job not proven yet, `None` if there is none or it failed for
+    /// good, as the batches after it cannot be proven before it.
+    pub fn next_to_prove(&self) -> Result<Option<Job>, QueueError> {
+        Ok(self
+            .jobs()?
+            .into_iter()
+            .find(|job| !matches!(job.status, JobStatus::Proven | JobStatus::Submitted))
+            .filter(|job| job.status == JobStatus::Pending))
+    }
+
+    /// Gives the jobs that failed for good another set of attempts.
+    pub fn reset_failed(&self) -> Result<(), QueueError> {
+        for mut job in self.jobs()? {
+            if let JobStatus::Failed(_) = job.status {
+                job.status = JobStatus::Pending;
+                job.attempts = 0;
+                self.update(&job)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn deserialize(bytes: &[u8]) -> Result<Job, QueueError> {
+    bincode::deserialize(bytes).map_err(|e| QueueError::Corrupted(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rollup_test_utils::temp_path;
+
+    use super::*;
+
+    #[test]
+    fn test_job_queue() {
+        let path = temp_path("prover-queue").with_extension("redb");
+        let queue = JobQueue::open(&path).unwrap();
+        queue.enqueue(3, Hash::new_unique()).unwrap();
+        queue.enqueue(4, Hash::new_unique()).unwrap();
+
+        let mut job = queue.next_to_prove().unwrap().unwrap();
+        assert_eq!(job.batch_number, 3);
+        job.status = JobStatus::Proven;
+        queue.update(&job).unwrap();
+        // Enqueued again as after a restart, the status is kept
+        queue.enqueue(3, job.pre_state_hash).unwrap();
+        assert_eq!(queue.get(3).unwrap().unwrap().status, JobStatus::Proven);
+
+        let mut job = queue.next_to_prove().unwrap().unwrap();
+        assert_eq!(job.batch_number, 4);
+        job.status = JobStatus::Failed("invalid input".to_string());
+        queue.update(&job).unwrap();
+        assert_eq!(queue.next_to_prove().unwrap(), None);
+        drop(queue);
+
+        let queue = JobQueue::open(&path).unwrap();
+        assert_eq!(queue.last().unwrap().unwrap().batch_number, 4);
+        queue.reset_failed().unwrap();
+        assert_eq!(queue.next_to_prove().unwrap().unwrap().batch_number, 4);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
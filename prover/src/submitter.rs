@@ -0,0 +1,87 @@
+use bridge_client::{
+    get_account, platform_address, send_batch_data, send_proof, Platform, ProveArgs,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use std::{error::Error, fs, sync::mpsc::Receiver, sync::Arc, thread, time::Duration};
+
+use crate::{
+    jobs::JobFiles,
+    queue::{JobQueue, JobStatus},
+};
+
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Publishes the batch data of proven batches and calls zk-bridge `prove`.
+pub struct Submitter {
+    client: RpcClient,
+    payer: Keypair,
+    platform_id: Pubkey,
+    queue: Arc<JobQueue>,
+    files: Arc<JobFiles>,
+}
+
+impl Submitter {
+    pub fn new(
+        client: RpcClient,
+        payer: Keypair,
+        platform_id: Pubkey,
+        queue: Arc<JobQueue>,
+        files: Arc<JobFiles>,
+    ) -> Self {
+        Submitter {
+            client,
+            payer,
+            platform_id,
+            queue,
+            files,
+        }
+    }
+
+    /// Submits the batches notified as proven, in order, until the prover
+    /// stops.
+    pub fn run(self, proven: Receiver<u64>) {
+        for batch_number in proven {
+            // L1 errors are transient, and the next batches need this one
+            while let Err(e) = self.submit(batch_number) {
+                eprintln!("Error: submitting batch {}: {}", batch_number, e);
+                thread::sleep(RETRY_DELAY);
+            }
+        }
+    }
+
+    fn submit(&self, batch_number: u64) -> Result<(), Box<dyn Error>> {
+        let platform: Platform = get_account(&self.client, &platform_address(&self.platform_id))?;
+        if platform.batch_number < batch_number {
+            return Err(format!("platform is at batch {}", platform.batch_number).into());
+        }
+        // The platform is past the batch if it landed before a restart
+        if platform.batch_number == batch_number {
+            let input = self.files.read_job(batch_number)?.input;
+            let batch_data = send_batch_data(
+                &self.client,
+                &self.payer,
+                &self.platform_id,
+                &bincode::serialize(&input.txs)?,
+            )?;
+            let args = ProveArgs {
+                proof: fs::read(self.files.onchain_proof_path(batch_number))?,
+                public_values: fs::read(self.files.public_values_path(batch_number))?,
+            };
+            let signature = send_proof(
+                &self.client,
+                &self.payer,
+                &self.platform_id,
+                &batch_data,
+                &args,
+            )?;
+            println!("Batch {} submitted: {}", batch_number, signature);
+        }
+
+        if let Some(mut job) = self.queue.get(batch_number)? {
+            job.status = JobStatus::Submitted;
+            self.queue.update(&job)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use clap::{Parser, ValueEnum};
+use rollup_test_utils::{
+    counter_account, funded_account, increment, program_account_v4, signed_transaction,
+    COUNTER_PROGRAM_ELF,
+};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    hash::Hash,
+    native_token::LAMPORTS_PER_SOL,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+};
+use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+use svm_runner::runner;
+use svm_runner_types::{ExecutionInput, RollupState};
+
+const ZK_SVM_ELF: &[u8] = include_elf!("zk-svm-program");
+
+/// Benchmarks synthetic batches.
+///
+/// Runs every combination of workload, transaction count and state size
+/// through `runner` natively and through the SP1 executor, and writes one CSV
+/// row per batch.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_values = ["transfers", "counter", "token"]
+    )]
+    workloads: Vec<Workload>,
+
+    /// Transactions per batch
+    #[clap(long, value_delimiter = ',', default_values = ["1", "10", "100"])]
+    txs: Vec<usize>,
+
+    /// Accounts in the state, padded with system accounts
+    #[clap(long, value_delimiter = ',', default_values = ["10", "1000", "100000"])]
+    accounts: Vec<usize>,
+
+    /// Skip the SP1 executor
+    #[clap(long)]
+    native_only: bool,
+
+    /// CSV output, stdout by default
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Workload {
+    /// System program transfers
+    Transfers,
+    /// Counter program increments
+    Counter,
+    /// SPL Token transfers
+    Token,
+}
+
+/// Tracks the peak of the bytes allocated by the native runs.
+struct PeakAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(allocated, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAlloc = PeakAlloc;
+
+fn main() {
+    let args = Args::parse();
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path).unwrap_or_else(|e| {
+            eprintln!("Error: {}: {}", path.display(), e);
+            std::process::exit(1);
+        })),
+        None => Box::new(io::stdout()),
+    };
+    writeln!(
+        output,
+        "workload,txs,accounts,failed_txs,native_ms,native_peak_bytes,sp1_ms,sp1_cycles,sp1_touched_memory_addresses"
+    )
+    .unwrap();
+
+    let client = ProverClient::builder().cpu().build();
+    for &workload in &args.workloads {
+        for &txs in &args.txs {
+            for &accounts in &args.accounts {
+                let input = synthetic_input(workload, txs, accounts);
+
+                let baseline = ALLOCATED.load(Ordering::Relaxed);
+                PEAK.store(baseline, Ordering::Relaxed);
+                let start = Instant::now();
+                let result = runner(&input).expect("batch failed");
+                let native_ms = start.elapsed().as_millis();
+                let native_peak_bytes = PEAK.load(Ordering::Relaxed) - baseline;
+                let failed_txs = result
+                    .receipts
+                    .iter()
+                    .filter(|receipt| receipt.status.is_err())
+                    .count();
+                drop(result);
+
+                let mut sp1 = [String::new(), String::new(), String::new()];
+                if !args.native_only {
+                    let mut stdin = SP1Stdin::new();
+                    stdin.write(&input);
+                    let start = Instant::now();
+                    let (_, report) = client
+                        .execute(ZK_SVM_ELF, &stdin)
+                        .run()
+                        .expect("execution failed");
+                    sp1 = [
+                        start.elapsed().as_millis().to_string(),
+                        report.total_instruction_count().to_string(),
+                        report.touched_memory_addresses.to_string(),
+                    ];
+                }
+
+                writeln!(
+                    output,
+                    "{},{},{},{},{},{},{}",
+                    workload.to_possible_value().unwrap().get_name(),
+                    txs,
+                    accounts,
+                    failed_txs,
+                    native_ms,
+                    native_peak_bytes,
+                    sp1.join(",")
+                )
+                .unwrap();
+                output.flush().unwrap();
+            }
+        }
+    }
+}
+
+/// A batch of `txs` transactions of `workload`, each signed by its own payer,
+/// over a state of at least `accounts` accounts.
+fn synthetic_input(workload: Workload, txs: usize, accounts: usize) -> ExecutionInput {
+    let mut state = Vec::new();
+    let mut transactions = Vec::with_capacity(txs);
+
+    let counter_program_id = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    match workload {
+        Workload::Transfers => {}
+        Workload::Counter => state.push((
+            counter_program_id,
+            program_account_v4(COUNTER_PROGRAM_ELF, Pubkey::default()),
+        )),
+        Workload::Token => {
+            let mint_state = Mint {
+                mint_authority: COption::None,
+                supply: txs as u64 * 1_000,
+                decimals: 6,
+                is_initialized: true,
+                freeze_authority: COption::None,
+            };
+            state.push((mint, packed_account(&spl_token::id(), &mint_state)));
+        }
+    }
+
+    for _ in 0..txs {
+        let payer = Keypair::new();
+        state.push((payer.pubkey(), funded_account(LAMPORTS_PER_SOL)));
+
+        let instruction = match workload {
+            Workload::Transfers => {
+                // Enough for the new account to be rent exempt
+                let lamports = LAMPORTS_PER_SOL / 10;
+                system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), lamports)
+            }
+            Workload::Counter => {
+                let counter = Pubkey::new_unique();
+                state.push((counter, counter_account(&counter_program_id)));
+                increment(&counter_program_id, &counter)
+            }
+            Workload::Token => {
+                let source = Pubkey::new_unique();
+                let destination = Pubkey::new_unique();
+                for (address, amount) in [(source, 1_000), (destination, 0)] {
+                    let token_account = TokenAccount {
+                        mint,
+                        owner: payer.pubkey(),
+                        amount,
+                        delegate: COption::None,
+                        state: AccountState::Initialized,
+                        is_native: COption::None,
+                        delegated_amount: 0,
+                        close_authority: COption::None,
+                    };
+                    state.push((address, packed_account(&spl_token::id(), &token_account)));
+                }
+                spl_token::instruction::transfer_checked(
+                    &spl_token::id(),
+                    &source,
+                    &mint,
+                    &destination,
+                    &payer.pubkey(),
+                    &[],
+                    100,
+                    6,
+                )
+                .unwrap()
+            }
+        };
+        transactions.push(signed_transaction(&[instruction], &[&payer]));
+    }
+
+    while state.len() < accounts {
+        state.push((Pubkey::new_unique(), funded_account(1)));
+    }
+
+    ExecutionInput {
+        batch_number: 0,
+        accounts: RollupState(state),
+        txs: transactions,
+        ramp_txs_start_hash: Hash::default(),
+        ramp_txs: vec![],
+        l1_messages_start_hash: Hash::default(),
+        l1_messages: vec![],
+    }
+}
+
+fn packed_account<T: Pack>(owner: &Pubkey, value: &T) -> AccountSharedData {
+    let mut data = vec![0; T::LEN];
+    value.pack_into_slice(&mut data);
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+    .into()
+}
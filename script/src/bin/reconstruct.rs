@@ -0,0 +1,166 @@
+use bridge_client::{get_account, get_batch_data, get_messages, get_ramp_txs, Batch, Platform};
+use clap::Parser;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, transaction::Transaction};
+use std::{io::Write, path::PathBuf};
+use svm_runner::runner;
+use svm_runner_types::{hash_state, public_values, ExecutionInput, L1Message, RampTx, RollupState};
+
+/// Rebuilds the rollup state from L1 alone.
+///
+/// Replays every proven batch of a platform from its published batch data,
+/// starting from the genesis state, and checks each batch against the roots
+/// accepted by zk-bridge `prove`.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+
+    #[clap(long)]
+    platform_id: Pubkey,
+
+    /// Initial state written by the `genesis` command
+    #[clap(long, default_value = "./genesis-state.bin")]
+    genesis_state_path: PathBuf,
+
+    /// Snapshot of the last state matching the roots on L1
+    #[clap(long, default_value = "./reconstructed-state.bin")]
+    state_output_path: PathBuf,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let genesis = std::fs::read(&args.genesis_state_path).expect("failed to read genesis state");
+    let mut state: RollupState = bincode::deserialize(&genesis).expect("invalid genesis state");
+
+    let client = RpcClient::new(args.rpc_url);
+    let platform: Platform =
+        get_account(&client, &bridge_client::platform_address(&args.platform_id)).unwrap_or_else(
+            |e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            },
+        );
+
+    // Position in the L1 queues after the last replayed batch
+    let mut ramp_head = 0;
+    let mut ramp_txs_hash = Hash::default();
+    let mut message_head = 0;
+    let mut message_queue_hash = Hash::default();
+    let mut divergent = None;
+
+    for batch_number in 0..platform.batch_number {
+        let (batch, txs, ramp_txs, l1_messages) = fetch_batch(
+            &client,
+            &args.platform_id,
+            batch_number,
+            ramp_head,
+            message_head,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Error: batch {}: {}", batch_number, e);
+            std::process::exit(1);
+        });
+
+        let input = ExecutionInput {
+            batch_number,
+            accounts: state.clone(),
+            txs,
+            ramp_txs_start_hash: ramp_txs_hash,
+            ramp_txs,
+            l1_messages_start_hash: message_queue_hash,
+            l1_messages,
+        };
+
+        let output = match runner(&input) {
+            Ok(output) => output,
+            Err(e) => {
+                println!("Batch {} failed to execute: {}", batch_number, e);
+                divergent = Some(batch_number);
+                break;
+            }
+        };
+
+        let values = public_values(&input, &output);
+        if values.post_state_hash != batch.state_hash
+            || values.txs_hash != batch.txs_hash
+            || values.outbox_root != batch.outbox_root
+            || values.withdrawals_root != batch.withdrawals_root
+        {
+            println!(
+                "Batch {} diverges: state hash {} on L1, {} replayed",
+                batch_number,
+                Hash::new_from_array(batch.state_hash),
+                Hash::new_from_array(values.post_state_hash)
+            );
+            divergent = Some(batch_number);
+            break;
+        }
+
+        println!(
+            "Batch {}: {}",
+            batch_number,
+            Hash::new_from_array(batch.state_hash)
+        );
+        state = output.state;
+        ramp_head = batch.ramp_head;
+        ramp_txs_hash = Hash::new_from_array(values.ramp_txs_hash);
+        message_head = batch.message_head;
+        message_queue_hash = Hash::new_from_array(values.l1_messages_hash);
+    }
+
+    let mut file = std::fs::File::create(&args.state_output_path).expect("failed to open file");
+    file.write_all(&bincode::serialize(&state).unwrap())
+        .unwrap();
+
+    match divergent {
+        Some(batch_number) => {
+            println!(
+                "First divergent batch: {}, wrote the state before it",
+                batch_number
+            );
+            std::process::exit(1);
+        }
+        None => println!(
+            "Replayed {} batches, final state hash: {}",
+            platform.batch_number,
+            hash_state(&state)
+        ),
+    }
+}
+
+type BatchContent = (Batch, Vec<Transaction>, Vec<RampTx>, Vec<L1Message>);
+
+/// Fetches a proven batch with its transactions and the ramp txs and L1
+/// messages it consumed from the queues.
+fn fetch_batch(
+    client: &RpcClient,
+    platform_id: &Pubkey,
+    batch_number: u64,
+    ramp_head: u64,
+    message_head: u64,
+) -> Result<BatchContent, Box<dyn std::error::Error>> {
+    let batch: Batch = get_account(
+        client,
+        &bridge_client::batch_address(platform_id, batch_number),
+    )?;
+    let txs = bincode::deserialize(&get_batch_data(client, &batch.batch_data)?)?;
+
+    let ramp_txs = get_ramp_txs(client, platform_id, ramp_head, batch.ramp_head - ramp_head)?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    let l1_messages = get_messages(
+        client,
+        platform_id,
+        message_head,
+        batch.message_head - message_head,
+    )?
+    .into_iter()
+    .map(Into::into)
+    .collect();
+
+    Ok((batch, txs, ramp_txs, l1_messages))
+}
@@ -0,0 +1,65 @@
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use svm_runner_types::{cycles, ExecutionInput};
+
+/// JSON report of the cycles spent in the guest regions, per transaction and
+/// per program ID.
+///
+/// Every cycle count is measured by a region of the guest. A transaction counts
+/// once, towards the comma-separated program IDs its instructions invoke, so
+/// the cycles of the programs add up to those of the transactions. Keys are
+/// sorted, so the reports of two commits can be diffed.
+pub fn cycles_report(
+    input: &ExecutionInput,
+    total_cycles: u64,
+    regions: &HashMap<String, u64>,
+) -> Value {
+    let region = |name: &str| regions.get(name).copied().unwrap_or_default();
+
+    let mut transactions = Vec::with_capacity(input.txs.len());
+    let mut transactions_cycles = 0;
+    let mut programs = BTreeMap::<String, (u64, u64)>::new();
+    for (index, tx) in input.txs.iter().enumerate() {
+        let cycles = region(&cycles::transaction_region(index));
+        transactions_cycles += cycles;
+        let program_ids: BTreeSet<String> = tx
+            .message
+            .instructions
+            .iter()
+            .filter_map(|ix| tx.message.account_keys.get(ix.program_id_index as usize))
+            .map(|program_id| program_id.to_string())
+            .collect();
+        let key = program_ids.iter().cloned().collect::<Vec<_>>().join(",");
+        let (count, total) = programs.entry(key).or_default();
+        *count += 1;
+        *total += cycles;
+        transactions.push(json!({
+            "index": index,
+            "signature": tx.signatures.first().map(|signature| signature.to_string()),
+            "programIds": program_ids,
+            "cycles": cycles,
+        }));
+    }
+
+    let programs: Map<String, Value> = programs
+        .into_iter()
+        .map(|(program_ids, (count, cycles))| {
+            let value = json!({ "transactions": count, "cycles": cycles });
+            (program_ids, value)
+        })
+        .collect();
+
+    json!({
+        "batchNumber": input.batch_number,
+        "totalCycles": total_cycles,
+        "regions": {
+            "rampTxs": region(cycles::RAMP_TXS),
+            "l1Messages": region(cycles::L1_MESSAGES),
+            "sanitize": region(cycles::SANITIZE),
+            "transactions": transactions_cycles,
+            "hashState": region(cycles::HASH_STATE),
+        },
+        "transactions": transactions,
+        "programs": programs,
+    })
+}
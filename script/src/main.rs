@@ -1,144 +1,487 @@
-use clap::Parser;
-use onchain_types::CommittedValues;
+use bridge_client::{
+    send_batch_data, send_proof, verify_onchain_proof, watcher::RampWatcher, ProveArgs,
+};
+use clap::{Args, Parser, Subcommand};
+use onchain_types::{PublicValues, ZK_BRIDGE_VKEY_HASH};
+use rollup_genesis::load_genesis;
+use rollup_test_utils::{
+    counter_account, counter_batch_on, funded_account, program_account_v4, COUNTER_PROGRAM_ELF,
+};
+use serde_json::json;
+use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    account::{Account, AccountSharedData, WritableAccount},
     hash::Hash,
-    instruction::{AccountMeta, Instruction, InstructionError},
-    loader_v4::{self, LoaderV4State, LoaderV4Status},
     native_token::LAMPORTS_PER_SOL,
+    pubkey,
     pubkey::Pubkey,
-    rent::Rent,
-    signature::Keypair,
+    signature::{read_keypair_file, Keypair},
     signer::Signer,
-    system_instruction, system_program,
-    transaction::Transaction,
 };
-use sp1_sdk::{include_elf, HashableKey, ProverClient, SP1Stdin};
+use sp1_sdk::{
+    include_elf, HashableKey, Prover, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1Stdin,
+};
 use std::{
-    fs::File,
-    io::{Read, Write},
+    fs,
+    path::{Path, PathBuf},
     vec,
 };
-use svm_runner_types::{hash_state, ExecutionInput, RampTx, RollupState};
+use svm_runner::config_hash;
+use svm_runner_types::{hash_state, ExecutionInput, RollupState};
+
+use crate::cycles::cycles_report;
+
+mod cycles;
 
 pub const ZK_SVM_ELF: &[u8] = include_elf!("zk-svm-program");
 
+/// Executes and proves zk-svm batches, and submits them to zk-bridge.
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Builds the initial rollup state from a genesis file, and the root and
+    /// config hash to pass to zk-bridge `create_platform`
+    Genesis(GenesisArgs),
+    /// Executes a batch without proving it
+    Execute(ExecuteArgs),
+    /// Generates the Groth16 proof of a batch
+    Prove(ProveCommandArgs),
+    /// Checks a saved proof against the verifying key of the program, and the
+    /// on-chain proof the way zk-bridge `prove` does
+    Verify(VerifyArgs),
+    /// Publishes the batch data and calls zk-bridge `prove` with a proof
+    Submit(SubmitArgs),
+    /// Decodes public values and proof files into JSON
+    Inspect(InspectArgs),
+    /// Checks the program verifying key hash against the one zk-bridge expects
+    Vkey,
+}
+
+#[derive(Args, Debug)]
+struct GenesisArgs {
+    /// Genesis file, `.toml` or `.json`
+    #[clap(long, default_value = "./genesis.toml")]
+    config: PathBuf,
+
+    #[clap(long, default_value = "./genesis-state.bin")]
+    state_output_path: PathBuf,
+
+    #[clap(long, default_value = "./genesis-root.json")]
+    root_output_path: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct InputArgs {
+    /// `ExecutionInput` of the batch, in bincode or in the JSON form of
+    /// `rollup_genesis::input` if it ends in `.json`, a test batch by default
     #[clap(long)]
-    execute: bool,
+    input: Option<PathBuf>,
 
+    /// Platform to take the batch number, ramp txs and L1 messages of the
+    /// batch from, instead of the input
     #[clap(long)]
-    prove: bool,
+    platform_id: Option<Pubkey>,
 
+    #[clap(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+
+    /// Slots to wait on top of the platform state before using it, read at
+    /// `finalized` commitment, or `confirmed` if 0
+    #[clap(long, default_value_t = 1)]
+    confirmations: u64,
+
+    /// Transactions of the batch, to publish with zk-bridge `publish_batch_data`
+    #[clap(long, default_value = "./batch-data.bin")]
+    batch_data_path: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct ExecuteArgs {
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[clap(long, default_value = "./onchain-public-values.bin")]
+    onchain_public_values_path: PathBuf,
+
+    /// JSON report of the cycles per region, transaction and program ID
     #[clap(long)]
-    input: Option<Vec<u8>>,
+    cycles_report_path: Option<PathBuf>,
+}
 
-    #[clap(long, short, default_value = "./sp1-proof.bin")]
-    sp1_output_path: String,
+#[derive(Args, Debug)]
+struct ProveCommandArgs {
+    #[command(flatten)]
+    input: InputArgs,
 
-    #[clap(long, short, default_value = "./onchain-commit.bin")]
-    onchain_commit_path: String,
+    #[clap(long, default_value = "./sp1-proof.bin")]
+    sp1_output_path: PathBuf,
 
-    #[clap(long, short, default_value = "./onchain-proof.bin")]
-    onchain_proof_path: String,
+    #[clap(long, default_value = "./onchain-public-values.bin")]
+    onchain_public_values_path: PathBuf,
+
+    #[clap(long, default_value = "./onchain-proof.bin")]
+    onchain_proof_path: PathBuf,
+
+    /// Generate an empty mock proof, only accepted by zk-bridge built with the
+    /// `mock-proofs` feature
+    #[clap(long)]
+    mock: bool,
 }
 
-// #[derive(Debug, BorshSerialize, BorshDeserialize)]
-// struct OnChainProof {
-//     pub public_values: Vec<u8>,
-//     pub proof: Vec<u8>,
-// }
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// Proof saved by `prove`
+    #[clap(long, default_value = "./sp1-proof.bin")]
+    sp1_proof_path: PathBuf,
+
+    #[clap(long, default_value = "./onchain-public-values.bin")]
+    onchain_public_values_path: PathBuf,
+
+    #[clap(long, default_value = "./onchain-proof.bin")]
+    onchain_proof_path: PathBuf,
+
+    /// Only check the SP1 proof
+    #[clap(long, conflicts_with = "onchain_only")]
+    sp1_only: bool,
+
+    /// Only check the on-chain proof, without setting up the prover
+    #[clap(long)]
+    onchain_only: bool,
+}
+
+#[derive(Args, Debug)]
+struct SubmitArgs {
+    #[clap(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+
+    /// Keypair paying for the batch data and the proof
+    #[clap(long)]
+    keypair: PathBuf,
+
+    #[clap(long)]
+    platform_id: Pubkey,
+
+    #[clap(long, default_value = "./batch-data.bin")]
+    batch_data_path: PathBuf,
+
+    #[clap(long, default_value = "./onchain-public-values.bin")]
+    onchain_public_values_path: PathBuf,
+
+    #[clap(long, default_value = "./onchain-proof.bin")]
+    onchain_proof_path: PathBuf,
+
+    /// Send the proof without checking it first, for proofs of a zk-bridge
+    /// that does not verify them
+    #[clap(long)]
+    skip_verify: bool,
+}
+
+#[derive(Args, Debug)]
+struct InspectArgs {
+    /// Public values written by `execute` or `prove`
+    #[clap(long)]
+    public_values: Option<PathBuf>,
+
+    /// Proof saved by `prove`
+    #[clap(long)]
+    sp1_proof: Option<PathBuf>,
+}
 
 fn main() {
-    let args = Args::parse();
+    match Cli::parse().command {
+        Command::Genesis(args) => genesis(args),
+        Command::Execute(args) => execute(args),
+        Command::Prove(args) => prove(args),
+        Command::Verify(args) => verify(args),
+        Command::Submit(args) => submit(args),
+        Command::Inspect(args) => inspect(args),
+        Command::Vkey => {
+            let (_, vk) = ProverClient::from_env().setup(ZK_SVM_ELF);
+            println!("Program: {}", vk.bytes32());
+            println!("zk-bridge: {}", ZK_BRIDGE_VKEY_HASH);
+            if vk.bytes32() != ZK_BRIDGE_VKEY_HASH {
+                exit("the program verifying key is not the one zk-bridge expects, update ZK_BRIDGE_VKEY_HASH");
+            }
+        }
+    }
+}
+
+fn genesis(args: GenesisArgs) {
+    let genesis = load_genesis(&args.config).unwrap_or_else(|e| exit(e));
+
+    write_file(
+        &args.state_output_path,
+        &bincode::serialize(&genesis.state).unwrap(),
+    );
+    let root = json!({
+        "root": genesis.root.to_string(),
+        "rootBytes": genesis.root.to_bytes(),
+        "configHash": config_hash().to_string(),
+        "configHashBytes": config_hash().to_bytes(),
+    });
+    write_file(
+        &args.root_output_path,
+        serde_json::to_string_pretty(&root).unwrap().as_bytes(),
+    );
 
-    if args.execute == args.prove {
-        eprintln!("Error: You must specify either --execute or --prove");
-        std::process::exit(1);
+    println!("Genesis accounts: {}", genesis.state.0.len());
+    println!("Initial state hash: {}", genesis.root);
+    println!("Config hash: {}", config_hash());
+}
+
+fn execute(args: ExecuteArgs) {
+    let input = load_input(&args.input);
+
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&input);
+    let (output, report) = ProverClient::from_env()
+        .execute(ZK_SVM_ELF, &stdin)
+        .run()
+        .unwrap_or_else(|e| exit(e));
+    println!("Program executed successfully.");
+    println!("Number of cycles: {}", report.total_instruction_count());
+
+    write_file(&args.onchain_public_values_path, &output.to_vec());
+    if let Some(path) = &args.cycles_report_path {
+        let cycles = cycles_report(
+            &input,
+            report.total_instruction_count(),
+            &report.cycle_tracker,
+        );
+        write_file(
+            path,
+            serde_json::to_string_pretty(&cycles).unwrap().as_bytes(),
+        );
     }
+}
+
+fn prove(args: ProveCommandArgs) {
+    let input = load_input(&args.input);
+    println!("Initial state hash: {}", hash_state(&input.accounts));
 
-    // Default to test input if user does not provide
-    let input = if let Some(input) = args.input {
-        bincode::deserialize(&input).unwrap()
+    let client = if args.mock {
+        ProverClient::builder().mock().build()
     } else {
-        create_test_input()
+        ProverClient::builder().cpu().build()
     };
-
-    // let bytes = bincode::serialize(&input).unwrap();
-    // let input = create_test_input();
-
-    let client = ProverClient::from_env();
     let mut stdin = SP1Stdin::new();
     stdin.write(&input);
+    let (pk, vk) = client.setup(ZK_SVM_ELF);
+    println!("Verifying key: {}", vk.bytes32());
+
+    println!("Starting proof generation...");
+    let mut proof = client
+        .prove(&pk, &stdin)
+        .groth16()
+        .run()
+        .expect("failed to generate proof");
+    proof
+        .save(&args.sp1_output_path)
+        .expect("failed to save proof");
+    write_file(
+        &args.onchain_public_values_path,
+        &proof.public_values.to_vec(),
+    );
+    write_file(&args.onchain_proof_path, &proof.bytes());
 
-    if args.execute {
-        // Execute the program
-        let (output, report) = client.execute(ZK_SVM_ELF, &stdin).run().unwrap();
-        println!("Program executed successfully.");
+    let public_values: PublicValues = proof.public_values.read();
+    println!(
+        "Final state hash: {}",
+        Hash::new_from_array(public_values.post_state_hash)
+    );
+    println!("Successfully generated proof!");
+}
 
-        // println!("output buffer: {}", output.raw());
+fn verify(args: VerifyArgs) {
+    let onchain = (!args.sp1_only).then(|| ProveArgs {
+        proof: read_file(&args.onchain_proof_path),
+        public_values: read_file(&args.onchain_public_values_path),
+    });
+
+    if !args.onchain_only {
+        let proof =
+            SP1ProofWithPublicValues::load(&args.sp1_proof_path).unwrap_or_else(|e| exit(e));
+        let client = ProverClient::from_env();
+        let (_, vk) = client.setup(ZK_SVM_ELF);
+        println!("Verifying key: {}", vk.bytes32());
+        client.verify(&proof, &vk).unwrap_or_else(|e| exit(e));
+        println!("SP1 proof is valid");
+
+        if vk.bytes32() != ZK_BRIDGE_VKEY_HASH {
+            eprintln!(
+                "Warning: zk-bridge only accepts proofs of verifying key {}",
+                ZK_BRIDGE_VKEY_HASH
+            );
+        }
+        if let Some(onchain) = &onchain {
+            if onchain.public_values != proof.public_values.to_vec() {
+                exit("the on-chain public values are not the ones of the SP1 proof");
+            }
+        }
+    }
 
-        // Record the number of cycles executed.
-        println!("Number of cycles: {}", report.total_instruction_count());
+    if let Some(onchain) = &onchain {
+        verify_onchain_proof(onchain).unwrap_or_else(|e| exit(e));
+        println!("On-chain proof is valid");
+    }
+}
 
-        let mut file =
-            std::fs::File::create(args.onchain_commit_path).expect("failed to open file");
-        file.write_all(&output.to_vec()).unwrap();
+fn submit(args: SubmitArgs) {
+    let payer = read_keypair_file(&args.keypair).unwrap_or_else(|e| exit(e));
+    let data = read_file(&args.batch_data_path);
+    let prove_args = ProveArgs {
+        proof: read_file(&args.onchain_proof_path),
+        public_values: read_file(&args.onchain_public_values_path),
+    };
+    // A rejected proof still costs the batch data and the transaction
+    if !args.skip_verify {
+        verify_onchain_proof(&prove_args).unwrap_or_else(|e| exit(e));
+    }
 
-        // let data: CommittedValues = output.read();
-        // println!("Committed values: {:?}", data);
-    } else {
-        println!("Initial state hash: {}", hash_state(input.accounts));
+    let client = RpcClient::new(args.rpc_url);
+    let batch_data =
+        send_batch_data(&client, &payer, &args.platform_id, &data).unwrap_or_else(|e| exit(e));
+    println!(
+        "Published {} bytes of batch data: {}",
+        data.len(),
+        batch_data
+    );
 
-        // Setup the program for proving.
-        let (pk, vk) = client.setup(ZK_SVM_ELF);
-        println!("Verifying key: {}", vk.bytes32());
+    let signature = send_proof(&client, &payer, &args.platform_id, &batch_data, &prove_args)
+        .unwrap_or_else(|e| exit(e));
+    println!("Proof accepted: {}", signature);
+}
+
+fn inspect(args: InspectArgs) {
+    let mut decoded = serde_json::Map::new();
+
+    if let Some(path) = &args.public_values {
+        let values = PublicValues::from_bytes(&read_file(path))
+            .unwrap_or_else(|| exit(format!("{}: invalid public values", path.display())));
+        decoded.insert("publicValues".to_string(), public_values_json(&values));
+    }
+    if let Some(path) = &args.sp1_proof {
+        let proof = SP1ProofWithPublicValues::load(path).unwrap_or_else(|e| exit(e));
+        let kind = match &proof.proof {
+            SP1Proof::Core(_) => "core",
+            SP1Proof::Compressed(_) => "compressed",
+            SP1Proof::Plonk(_) => "plonk",
+            SP1Proof::Groth16(_) => "groth16",
+        };
+        let public_values = proof.public_values.to_vec();
+        decoded.insert(
+            "sp1Proof".to_string(),
+            json!({
+                "kind": kind,
+                "sp1Version": proof.sp1_version,
+                "publicValues": PublicValues::from_bytes(&public_values)
+                    .as_ref()
+                    .map(public_values_json),
+                // What zk-bridge `prove` takes, only Groth16 and Plonk proofs have one
+                "onchainProofSize": matches!(kind, "groth16" | "plonk").then(|| proof.bytes().len()),
+            }),
+        );
+    }
 
-        println!("Starting proof generation...");
-        let mut proof = client
-            .prove(&pk, &stdin)
-            .groth16()
-            .run()
-            .expect("failed to generate proof");
-        proof
-            .save(args.sp1_output_path)
-            .expect("failed to save proof");
-
-        let mut file =
-            std::fs::File::create(args.onchain_commit_path).expect("failed to open file");
-        file.write_all(&proof.public_values.to_vec()).unwrap();
-
-        let mut file = std::fs::File::create(args.onchain_proof_path).expect("failed to open file");
-        file.write_all(&proof.bytes()).unwrap();
-
-        // let onchain_proof = OnChainProof {
-        //     public_values: proof.public_values.to_vec(),
-        //     proof: proof.bytes(),
-        // };
-        // let serialized_data = borsh::to_vec(&onchain_proof).unwrap();
-
-        // let mut file = std::fs::File::create(args.onchain_output_path).expect("failed to open file");
-        // file.write_all(&serialized_data).unwrap();
-        // bincode
-        //     ::serialize_into(
-        //         std::fs::File::create(args.onchain_output_path).expect("failed to open file"),
-        //         &onchain_proof
-        //     )
-        //     .unwrap();
-
-        let commit: CommittedValues = proof.public_values.read();
-        println!("Final state hash: {:?}", commit.output);
-
-        println!("Successfully generated proof!");
-
-        // Verify the proof.
-        // client.verify(&proof, &vk).expect("failed to verify proof");
-        // println!("Successfully verified proof!");
+    if decoded.is_empty() {
+        exit("nothing to inspect, pass --public-values or --sp1-proof");
     }
+    println!("{}", serde_json::to_string_pretty(&decoded).unwrap());
+}
+
+fn public_values_json(values: &PublicValues) -> serde_json::Value {
+    let hash = |bytes: &[u8; 32]| Hash::new_from_array(*bytes).to_string();
+    json!({
+        "batchNumber": values.batch_number,
+        "preStateHash": hash(&values.pre_state_hash),
+        "postStateHash": hash(&values.post_state_hash),
+        "rampTxsStartHash": hash(&values.ramp_txs_start_hash),
+        "rampTxsHash": hash(&values.ramp_txs_hash),
+        "rampTxsCount": values.ramp_txs_count,
+        "l1MessagesStartHash": hash(&values.l1_messages_start_hash),
+        "l1MessagesHash": hash(&values.l1_messages_hash),
+        "l1MessagesCount": values.l1_messages_count,
+        "txsHash": hash(&values.txs_hash),
+        "outboxRoot": hash(&values.outbox_root),
+        "withdrawalsRoot": hash(&values.withdrawals_root),
+        "configHash": hash(&values.config_hash),
+    })
 }
 
+/// Reads the input of a batch and writes its batch data.
+fn load_input(args: &InputArgs) -> ExecutionInput {
+    let mut input: ExecutionInput = match &args.input {
+        Some(path) => rollup_genesis::input::load_input(path)
+            .unwrap_or_else(|e| exit(format!("{}: {}", path.display(), e))),
+        None => create_test_input(),
+    };
+
+    if let Some(platform_id) = args.platform_id {
+        let client = RpcClient::new(args.rpc_url.clone());
+        let next = RampWatcher::new(&client, platform_id, args.confirmations)
+            .next_batch()
+            .unwrap_or_else(|e| exit(e));
+        // `prove` rejects a proof that does not start from the platform state
+        if hash_state(&input.accounts) != next.pre_state_hash {
+            exit(format!(
+                "input state hash {} does not match the platform state hash {}",
+                hash_state(&input.accounts),
+                next.pre_state_hash
+            ));
+        }
+        println!(
+            "Batch {} at slot {}: {} ramp txs, {} L1 messages",
+            next.batch_number,
+            next.slot,
+            next.ramp_txs.len(),
+            next.l1_messages.len()
+        );
+        // Nor one executed with another configuration
+        if config_hash() != next.config_hash {
+            exit(format!(
+                "runner config hash {} does not match the platform config hash {}",
+                config_hash(),
+                next.config_hash
+            ));
+        }
+        input.batch_number = next.batch_number;
+        input.ramp_txs_start_hash = next.ramp_txs_start_hash;
+        input.ramp_txs = next.ramp_txs;
+        input.l1_messages_start_hash = next.l1_messages_start_hash;
+        input.l1_messages = next.l1_messages;
+    }
+
+    write_file(
+        &args.batch_data_path,
+        &bincode::serialize(&input.txs).unwrap(),
+    );
+    input
+}
+
+fn read_file(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|e| exit(format!("{}: {}", path.display(), e)))
+}
+
+fn write_file(path: &Path, data: &[u8]) {
+    fs::write(path, data).unwrap_or_else(|e| exit(format!("{}: {}", path.display(), e)))
+}
+
+fn exit(err: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", err);
+    std::process::exit(1);
+}
+
+/// Counter program and counter of `genesis.toml`
+const COUNTER_PROGRAM_ID: Pubkey = pubkey!("RpXAja7ZvyqmCqS2k13hydLoumcZ76Mk4tHVAnhfDHD");
+const COUNTER: Pubkey = pubkey!("Gpi483RBiJ6UsPzkb1urzTyNc9Ceb68MAGuhY6RaQoum");
+
+/// The batch the zk-bridge tests prove, on the state of `genesis.toml` and
+/// with the 1 SOL onramp they queue.
 fn create_test_input() -> ExecutionInput {
     let kp_sender_bytes: Vec<u8> =
         serde_json::from_slice(include_bytes!("../../onchain/tests/keypairSender.json")).unwrap();
@@ -147,109 +490,26 @@ fn create_test_input() -> ExecutionInput {
     let kp_receiver_bytes: Vec<u8> =
         serde_json::from_slice(include_bytes!("../../onchain/tests/keypairReceiver.json")).unwrap();
     let kp_receiver = Keypair::from_bytes(&kp_receiver_bytes).unwrap();
-    let pk_receiver = kp_receiver.pubkey();
-    let pk_sender = kp_sender.pubkey();
-
-    let counter_program_id = Keypair::new().pubkey();
-    let pk_counter = Keypair::new().pubkey();
-
-    let path = "../counter-program/counter_program.so";
-    let mut file = File::open(path).expect("file open failed");
-    let mut elf_bytes = Vec::new();
-    file.read_to_end(&mut elf_bytes).unwrap();
-    let rent = Rent::default();
-    let account_size = LoaderV4State::program_data_offset().saturating_add(elf_bytes.len());
-    let mut program_account = AccountSharedData::new(
-        rent.minimum_balance(account_size),
-        account_size,
-        &loader_v4::id(),
-    );
-    let state = get_state_mut(program_account.data_as_mut_slice()).unwrap();
-    state.slot = 0;
-    state.authority_address_or_next_version = Pubkey::new_unique();
-    state.status = LoaderV4Status::Deployed;
-    program_account.data_as_mut_slice()[LoaderV4State::program_data_offset()..]
-        .copy_from_slice(&elf_bytes);
-
-    ExecutionInput {
-        accounts: RollupState(vec![
-            (
-                pk_sender,
-                Account {
-                    lamports: 0,
-                    data: vec![],
-                    owner: system_program::id(),
-                    executable: false,
-                    rent_epoch: 0,
-                }
-                .into(),
-            ),
-            (
-                pk_receiver,
-                Account {
-                    lamports: 0,
-                    data: vec![],
-                    owner: system_program::id(),
-                    executable: false,
-                    rent_epoch: 0,
-                }
-                .into(),
-            ),
-            (counter_program_id, program_account),
-            (
-                pk_counter,
-                Account {
-                    lamports: 100000,
-                    data: vec![0, 0, 0, 0],
-                    owner: counter_program_id,
-                    executable: false,
-                    rent_epoch: 0,
-                }
-                .into(),
-            ),
-        ]),
-        txs: vec![
-            Transaction::new_signed_with_payer(
-                &[system_instruction::transfer(
-                    &pk_sender,
-                    &pk_receiver,
-                    LAMPORTS_PER_SOL,
-                )],
-                Some(&pk_sender),
-                &[&kp_sender],
-                Hash::new_from_array([7; 32]),
-            ),
-            Transaction::new_signed_with_payer(
-                &[Instruction {
-                    program_id: counter_program_id,
-                    accounts: vec![AccountMeta::new(pk_counter, false)],
-                    data: vec![],
-                }],
-                Some(&pk_sender),
-                &[&kp_sender],
-                Hash::new_from_array([7; 32]),
-            ),
-        ],
-        ramp_txs: vec![RampTx {
-            is_onramp: true,
-            user: pk_sender,
-            amount: 10 * LAMPORTS_PER_SOL,
-        }],
-    }
-}
 
-fn get_state_mut(data: &mut [u8]) -> Result<&mut LoaderV4State, InstructionError> {
-    unsafe {
-        let data = data
-            .get_mut(0..LoaderV4State::program_data_offset())
-            .ok_or(InstructionError::AccountDataTooSmall)?
-            .try_into()
-            .unwrap();
-        Ok(std::mem::transmute::<
-            &mut [u8; LoaderV4State::program_data_offset()],
-            &mut LoaderV4State,
-        >(data))
-    }
+    // In the order of `genesis.toml`, accounts before programs
+    let accounts = RollupState(vec![
+        (kp_sender.pubkey(), funded_account(0)),
+        (kp_receiver.pubkey(), funded_account(0)),
+        (COUNTER, counter_account(&COUNTER_PROGRAM_ID)),
+        (
+            COUNTER_PROGRAM_ID,
+            program_account_v4(COUNTER_PROGRAM_ELF, Pubkey::default()),
+        ),
+    ]);
+
+    counter_batch_on(
+        accounts,
+        &kp_sender,
+        &kp_receiver.pubkey(),
+        &COUNTER_PROGRAM_ID,
+        &COUNTER,
+        LAMPORTS_PER_SOL,
+    )
 }
 
 /*
@@ -303,3 +563,16 @@ fn test_convert_proof() {
     //     .unwrap();
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_test_input() {
+        // The zk-bridge tests create the platform from the root of the genesis
+        let genesis =
+            load_genesis(&Path::new(env!("CARGO_MANIFEST_DIR")).join("genesis.toml")).unwrap();
+        assert_eq!(hash_state(&create_test_input().accounts), genesis.root);
+    }
+}
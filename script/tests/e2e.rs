@@ -0,0 +1,214 @@
+//! End-to-end test of a batch on a local validator, with a mock proof.
+//!
+//! zk-bridge must be built with the `mock-proofs` feature first, which needs
+//! `localnet` too. `.github/workflows/e2e.yml` runs it in CI:
+//!
+//! ```sh
+//! cd onchain && anchor build -- --features mock-proofs,localnet
+//! cd script && cargo test --release --test e2e -- --ignored
+//! ```
+
+use bridge_client::{
+    add_ramp_tx, create_platform, get_account, platform_address, send_batch_data, send_proof,
+    watcher::RampWatcher, withdraw, AddRampTxArgs, CreatePlatformArgs, Platform, ProveArgs,
+    WithdrawArgs,
+};
+use onchain_types::PublicValues;
+use rollup_test_utils::temp_path;
+use solana_client::{client_error::ClientError, rpc_client::RpcClient};
+use solana_sdk::{
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+use sp1_sdk::{include_elf, Prover, ProverClient, SP1Stdin};
+use std::{
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+use svm_runner::{config_hash, runner};
+use svm_runner_types::{hash_state, withdrawal_proof, ExecutionInput, RollupState};
+
+const ZK_SVM_ELF: &[u8] = include_elf!("zk-svm-program");
+
+const RPC_PORT: u16 = 18899;
+const FAUCET_PORT: u16 = 19900;
+
+/// Deposits, executes and proves a batch withdrawing half of the deposit, then
+/// claims the withdrawal on L1.
+#[test]
+#[ignore = "needs solana-test-validator and zk-bridge built with the mock-proofs feature"]
+fn test_mock_proof_pipeline() {
+    let _validator = TestValidator::start();
+    let client = RpcClient::new(format!("http://127.0.0.1:{}", RPC_PORT));
+    wait_for_validator(&client);
+
+    let user = Keypair::new();
+    airdrop(&client, &user.pubkey(), 10 * LAMPORTS_PER_SOL);
+
+    let platform_id = Pubkey::new_unique();
+    let genesis = RollupState(vec![]);
+    let create = create_platform(
+        &user.pubkey(),
+        &CreatePlatformArgs {
+            id: platform_id,
+            initial_state_hash: hash_state(&genesis).to_bytes(),
+            config_hash: config_hash().to_bytes(),
+        },
+    );
+    send(&client, &user, create).unwrap();
+
+    for (is_onramp, amount) in [(true, LAMPORTS_PER_SOL), (false, LAMPORTS_PER_SOL / 2)] {
+        let args = AddRampTxArgs { is_onramp, amount };
+        send(
+            &client,
+            &user,
+            add_ramp_tx(&platform_id, &user.pubkey(), 0, &args),
+        )
+        .unwrap();
+    }
+
+    // Execute
+    let next = RampWatcher::new(&client, platform_id, 0)
+        .next_batch()
+        .unwrap();
+    assert_eq!(next.ramp_txs.len(), 2);
+    let input = ExecutionInput {
+        batch_number: next.batch_number,
+        accounts: genesis,
+        txs: vec![],
+        ramp_txs_start_hash: next.ramp_txs_start_hash,
+        ramp_txs: next.ramp_txs,
+        l1_messages_start_hash: next.l1_messages_start_hash,
+        l1_messages: next.l1_messages,
+    };
+    let output = runner(&input).unwrap();
+    assert_eq!(output.withdrawals.len(), 1);
+
+    // Prove
+    let prover = ProverClient::builder().mock().build();
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&input);
+    let (pk, _) = prover.setup(ZK_SVM_ELF);
+    let proof = prover.prove(&pk, &stdin).groth16().run().unwrap();
+    let args = ProveArgs {
+        proof: proof.bytes(),
+        public_values: proof.public_values.to_vec(),
+    };
+    let values = PublicValues::from_bytes(&args.public_values).unwrap();
+    assert_eq!(values.post_state_hash, hash_state(&output.state).to_bytes());
+
+    // Upload the batch data and submit the proof
+    let data = bincode::serialize(&input.txs).unwrap();
+    let batch_data = send_batch_data(&client, &user, &platform_id, &data).unwrap();
+    send_proof(&client, &user, &platform_id, &batch_data, &args).unwrap();
+
+    let platform: Platform = get_account(&client, &platform_address(&platform_id)).unwrap();
+    assert_eq!(platform.batch_number, 1);
+    assert_eq!(platform.last_state_hash, values.post_state_hash);
+    assert_eq!(platform.ramp_head, 2);
+
+    // Withdraw
+    let args = WithdrawArgs {
+        batch_number: 0,
+        index: 0,
+        amount: LAMPORTS_PER_SOL / 2,
+        proof: withdrawal_proof(&output.withdrawals, 0),
+    };
+    send(
+        &client,
+        &user,
+        withdraw(&platform_id, &user.pubkey(), &args),
+    )
+    .unwrap();
+
+    let platform: Platform = get_account(&client, &platform_address(&platform_id)).unwrap();
+    assert_eq!(platform.withdraw, LAMPORTS_PER_SOL / 2);
+    // A withdrawal is claimed once
+    assert!(send(
+        &client,
+        &user,
+        withdraw(&platform_id, &user.pubkey(), &args)
+    )
+    .is_err());
+}
+
+/// `solana-test-validator` with zk-bridge deployed, killed on drop.
+struct TestValidator {
+    child: Child,
+    ledger: PathBuf,
+}
+
+impl TestValidator {
+    fn start() -> Self {
+        let program = std::env::var("ZK_BRIDGE_SO")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("../onchain/target/deploy/zk_bridge.so")
+            });
+        assert!(program.exists(), "{} not found", program.display());
+        let ledger = temp_path("zk-svm-e2e-ledger");
+
+        let child = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .args(["--rpc-port", &RPC_PORT.to_string()])
+            .args(["--faucet-port", &FAUCET_PORT.to_string()])
+            .arg("--bpf-program")
+            .arg(bridge_client::ID.to_string())
+            .arg(&program)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("failed to start solana-test-validator");
+
+        TestValidator { child, ledger }
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+fn wait_for_validator(client: &RpcClient) {
+    let start = Instant::now();
+    while client.get_health().is_err() {
+        assert!(
+            start.elapsed() < Duration::from_secs(60),
+            "validator did not start"
+        );
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn airdrop(client: &RpcClient, to: &Pubkey, lamports: u64) {
+    let signature = client.request_airdrop(to, lamports).unwrap();
+    while !client.confirm_transaction(&signature).unwrap() {
+        thread::sleep(Duration::from_millis(200));
+    }
+}
+
+fn send(
+    client: &RpcClient,
+    payer: &Keypair,
+    instruction: Instruction,
+) -> Result<Signature, ClientError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        client.get_latest_blockhash()?,
+    );
+    client.send_and_confirm_transaction(&transaction)
+}
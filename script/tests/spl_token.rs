@@ -0,0 +1,146 @@
+use onchain_types::PublicValues;
+use rollup_test_utils::{funded_account, onramp, signed_transaction};
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount},
+    hash::Hash,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+};
+use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
+use spl_associated_token_account_client::{
+    address::get_associated_token_address, instruction::create_associated_token_account,
+};
+use spl_token::{
+    instruction::{burn, initialize_mint2, mint_to, transfer_checked},
+    state::{Account as TokenAccount, Mint},
+};
+use svm_runner::runner;
+use svm_runner_types::{hash_state, ExecutionInput, RollupState};
+
+const ZK_SVM_ELF: &[u8] = include_elf!("zk-svm-program");
+
+/// Mints, transfers and burns tokens in a single batch proven with the mock prover.
+#[test]
+fn test_spl_token_batch() {
+    let kp_payer = Keypair::new();
+    let kp_receiver = Keypair::new();
+    let kp_mint = Keypair::new();
+    let pk_payer = kp_payer.pubkey();
+    let pk_mint = kp_mint.pubkey();
+    let pk_payer_ata = get_associated_token_address(&pk_payer, &pk_mint);
+    let pk_receiver_ata = get_associated_token_address(&kp_receiver.pubkey(), &pk_mint);
+
+    let input = ExecutionInput {
+        batch_number: 0,
+        accounts: RollupState(vec![
+            (pk_payer, funded_account(0)),
+            (kp_receiver.pubkey(), funded_account(0)),
+        ]),
+        txs: vec![
+            signed_transaction(
+                &[
+                    system_instruction::create_account(
+                        &pk_payer,
+                        &pk_mint,
+                        Rent::default().minimum_balance(Mint::LEN),
+                        Mint::LEN as u64,
+                        &spl_token::id(),
+                    ),
+                    initialize_mint2(&spl_token::id(), &pk_mint, &pk_payer, None, 6).unwrap(),
+                ],
+                &[&kp_payer, &kp_mint],
+            ),
+            signed_transaction(
+                &[
+                    create_associated_token_account(
+                        &pk_payer,
+                        &pk_payer,
+                        &pk_mint,
+                        &spl_token::id(),
+                    ),
+                    create_associated_token_account(
+                        &pk_payer,
+                        &kp_receiver.pubkey(),
+                        &pk_mint,
+                        &spl_token::id(),
+                    ),
+                    mint_to(
+                        &spl_token::id(),
+                        &pk_mint,
+                        &pk_payer_ata,
+                        &pk_payer,
+                        &[],
+                        1_000,
+                    )
+                    .unwrap(),
+                ],
+                &[&kp_payer],
+            ),
+            signed_transaction(
+                &[
+                    transfer_checked(
+                        &spl_token::id(),
+                        &pk_payer_ata,
+                        &pk_mint,
+                        &pk_receiver_ata,
+                        &pk_payer,
+                        &[],
+                        400,
+                        6,
+                    )
+                    .unwrap(),
+                    burn(
+                        &spl_token::id(),
+                        &pk_payer_ata,
+                        &pk_mint,
+                        &pk_payer,
+                        &[],
+                        100,
+                    )
+                    .unwrap(),
+                ],
+                &[&kp_payer],
+            ),
+        ],
+        ramp_txs_start_hash: Hash::default(),
+        ramp_txs: vec![onramp(&pk_payer, LAMPORTS_PER_SOL)],
+        l1_messages_start_hash: Hash::default(),
+        l1_messages: vec![],
+    };
+
+    let output = runner(&input).unwrap().state;
+
+    let mint = Mint::unpack(get_account(&output, &pk_mint).data()).unwrap();
+    assert_eq!(mint.supply, 900);
+    let payer_ata = TokenAccount::unpack(get_account(&output, &pk_payer_ata).data()).unwrap();
+    assert_eq!(payer_ata.amount, 500);
+    let receiver_ata = TokenAccount::unpack(get_account(&output, &pk_receiver_ata).data()).unwrap();
+    assert_eq!(receiver_ata.amount, 400);
+
+    let client = ProverClient::builder().mock().build();
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&input);
+    let (pk, vk) = client.setup(ZK_SVM_ELF);
+    let mut proof = client.prove(&pk, &stdin).run().unwrap();
+    client.verify(&proof, &vk).unwrap();
+
+    let public_values: PublicValues = proof.public_values.read();
+    assert_eq!(
+        public_values.post_state_hash,
+        hash_state(&output).to_bytes()
+    );
+    assert_eq!(
+        public_values.pre_state_hash,
+        hash_state(&input.accounts).to_bytes()
+    );
+    assert_eq!(public_values.ramp_txs_count, 1);
+}
+
+fn get_account<'a>(state: &'a RollupState, pubkey: &Pubkey) -> &'a AccountSharedData {
+    &state.0.iter().find(|(pk, _)| pk == pubkey).unwrap().1
+}
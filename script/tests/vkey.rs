@@ -0,0 +1,18 @@
+//! zk-bridge verifies proofs against the verifying key hash in
+//! `onchain_types`, which changes with every change to the zk-svm program.
+
+use onchain_types::ZK_BRIDGE_VKEY_HASH;
+use sp1_sdk::{include_elf, HashableKey, ProverClient};
+
+const ZK_SVM_ELF: &[u8] = include_elf!("zk-svm-program");
+
+#[test]
+fn test_vkey_hash() {
+    // Setting up the mock prover computes the real verifying key
+    let (_, vk) = ProverClient::builder().mock().build().setup(ZK_SVM_ELF);
+    assert_eq!(
+        vk.bytes32(),
+        ZK_BRIDGE_VKEY_HASH,
+        "ZK_BRIDGE_VKEY_HASH is stale, update it to the hash printed by `cargo run --release -- vkey`"
+    );
+}
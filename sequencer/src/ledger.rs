@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    account::AccountSharedData,
+    hash::{hashv, Hash},
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::Arc,
+};
+use svm_runner_types::{RollupState, TransactionReceipt};
+
+use crate::store::SequencerState;
+
+/// Batches a blockhash stays valid for, as `MAX_RECENT_BLOCKHASHES` on L1
+pub const MAX_RECENT_BLOCKHASHES: usize = 300;
+
+/// Stage of a batch, named after the L1 commitment levels.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Commitment {
+    /// Executed by the sequencer
+    Processed,
+    /// Its proof is generated
+    Confirmed,
+    /// Its root was accepted by zk-bridge `prove`
+    #[default]
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+/// What a batch changed, to notify subscribers at each of its stages.
+#[derive(Debug)]
+pub struct BatchUpdate {
+    pub slot: u64,
+    /// Accounts written by the batch, closed ones are empty
+    pub accounts: Vec<(Pubkey, AccountSharedData)>,
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// A batch reaching a stage.
+#[derive(Debug, Clone)]
+pub struct StageUpdate {
+    pub commitment: Commitment,
+    pub batch: Arc<BatchUpdate>,
+}
+
+/// A transaction executed in a batch.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct TransactionRecord {
+    pub slot: u64,
+    pub transaction: Transaction,
+    pub receipt: TransactionReceipt,
+}
+
+/// Rollup state after the last batch, as served over RPC. A batch is a slot.
+pub struct Ledger {
+    /// Number of executed batches
+    pub slot: u64,
+    pub accounts: HashMap<Pubkey, AccountSharedData>,
+    /// Blockhashes transactions can use, oldest first
+    pub blockhashes: VecDeque<Hash>,
+    /// Last slot whose proof is generated
+    pub confirmed_slot: u64,
+    /// Last slot accepted by zk-bridge
+    pub finalized_slot: u64,
+    /// Batches not finalized yet
+    pending: BTreeMap<u64, Arc<BatchUpdate>>,
+}
+
+impl Ledger {
+    pub fn new(state: &SequencerState) -> Self {
+        Ledger {
+            slot: state.batch_number,
+            accounts: state.accounts.0.iter().cloned().collect(),
+            blockhashes: state.blockhashes.clone(),
+            confirmed_slot: 0,
+            finalized_slot: 0,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub fn commitment(&self, slot: u64) -> Commitment {
+        if slot <= self.finalized_slot {
+            Commitment::Finalized
+        } else if slot <= self.confirmed_slot {
+            Commitment::Confirmed
+        } else {
+            Commitment::Processed
+        }
+    }
+
+    pub fn latest_blockhash(&self) -> Hash {
+        *self.blockhashes.back().unwrap()
+    }
+
+    pub fn is_valid_blockhash(&self, blockhash: &Hash) -> bool {
+        self.blockhashes.contains(blockhash)
+    }
+
+    /// Last slot a transaction using the latest blockhash can land in
+    pub fn last_valid_slot(&self) -> u64 {
+        self.slot + MAX_RECENT_BLOCKHASHES as u64
+    }
+
+    /// Accounts in the order of the rollup state, to execute a transaction on
+    pub fn rollup_state(&self) -> RollupState {
+        RollupState(
+            self.accounts
+                .iter()
+                .map(|(pubkey, account)| (*pubkey, account.clone()))
+                .collect(),
+        )
+    }
+
+    /// Moves to the state after a batch, `written` being the accounts it
+    /// wrote.
+    pub fn apply_batch(
+        &mut self,
+        state: &SequencerState,
+        written: Vec<(Pubkey, AccountSharedData)>,
+        receipts: &[TransactionReceipt],
+    ) -> StageUpdate {
+        self.slot = state.batch_number;
+        self.accounts = state.accounts.0.iter().cloned().collect();
+        self.blockhashes = state.blockhashes.clone();
+
+        let batch = Arc::new(BatchUpdate {
+            slot: self.slot,
+            accounts: written,
+            receipts: receipts.to_vec(),
+        });
+        self.pending.insert(self.slot, batch.clone());
+        StageUpdate {
+            commitment: Commitment::Processed,
+            batch,
+        }
+    }
+
+    /// Marks the batches up to `slot` as proven.
+    pub fn confirm(&mut self, slot: u64) -> Vec<StageUpdate> {
+        let slot = slot.min(self.slot);
+        let updates = self.stage_updates(self.confirmed_slot, slot, Commitment::Confirmed);
+        self.confirmed_slot = self.confirmed_slot.max(slot);
+        updates
+    }
+
+    /// Marks the batches up to `slot` as accepted on L1, which implies proven.
+    pub fn finalize(&mut self, slot: u64) -> Vec<StageUpdate> {
+        let slot = slot.min(self.slot);
+        let mut updates = self.confirm(slot);
+        updates.extend(self.stage_updates(self.finalized_slot, slot, Commitment::Finalized));
+        self.finalized_slot = self.finalized_slot.max(slot);
+        self.pending = self.pending.split_off(&(self.finalized_slot + 1));
+        updates
+    }
+
+    fn stage_updates(&self, from: u64, to: u64, commitment: Commitment) -> Vec<StageUpdate> {
+        if to <= from {
+            return Vec::new();
+        }
+        self.pending
+            .range(from + 1..=to)
+            .map(|(_, batch)| StageUpdate {
+                commitment,
+                batch: batch.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Blockhash of a batch, chained to the previous one.
+fn next_blockhash(previous: &Hash, state_hash: &Hash) -> Hash {
+    hashv(&[previous.as_ref(), state_hash.as_ref()])
+}
+
+/// Appends the blockhash of a new batch, dropping expired ones.
+pub fn push_blockhash(blockhashes: &mut VecDeque<Hash>, state_hash: &Hash) {
+    let blockhash = next_blockhash(blockhashes.back().unwrap(), state_hash);
+    blockhashes.push_back(blockhash);
+    while blockhashes.len() > MAX_RECENT_BLOCKHASHES {
+        blockhashes.pop_front();
+    }
+}
@@ -0,0 +1,174 @@
+use clap::Parser;
+use rollup_genesis::load_genesis;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use svm_runner_types::hash_state;
+use tokio::sync::broadcast;
+
+use crate::{
+    ledger::Ledger,
+    mempool::Mempool,
+    sequencer::{BatchLimits, Sequencer},
+    store::{load_snapshot, SequencerState, Store},
+};
+
+mod ledger;
+mod mempool;
+mod pubsub;
+mod rpc;
+mod sequencer;
+mod store;
+
+/// Rollup sequencer.
+///
+/// Serves a Solana JSON-RPC subset over the rollup. Cuts batches with the
+/// transactions sent to it and the ramp txs and L1 messages queued on a
+/// zk-bridge platform, executes them and writes a prover job per batch to
+/// `<data-dir>/jobs`.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// L1 RPC, `solana-test-validator` by default
+    #[clap(long, default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+
+    #[clap(long)]
+    platform_id: Pubkey,
+
+    /// Genesis file the rollup starts from if `data_dir` holds no state yet
+    #[clap(long, default_value = "./genesis.toml")]
+    genesis: PathBuf,
+
+    /// Snapshot the rollup starts from instead of the genesis if `data_dir`
+    /// holds no state yet
+    #[clap(long)]
+    snapshot: Option<PathBuf>,
+
+    #[clap(long, default_value = "./sequencer-data")]
+    data_dir: PathBuf,
+
+    /// Address of the JSON-RPC server
+    #[clap(long, default_value = "127.0.0.1:8999")]
+    listen: SocketAddr,
+
+    /// Address of the PubSub server, the port after the JSON-RPC one by
+    /// default as clients expect
+    #[clap(long)]
+    ws_listen: Option<SocketAddr>,
+
+    #[clap(long, default_value_t = 1000)]
+    batch_interval_ms: u64,
+
+    #[clap(long, default_value_t = 256)]
+    max_batch_txs: usize,
+
+    #[clap(long, default_value_t = 64)]
+    max_ramp_txs: u64,
+
+    #[clap(long, default_value_t = 64)]
+    max_l1_messages: u64,
+
+    #[clap(long, default_value_t = 10_000)]
+    mempool_capacity: usize,
+
+    /// Batches between full snapshots in `<data-dir>/snapshots`, 0 to disable
+    #[clap(long, default_value_t = 100)]
+    snapshot_interval: u64,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let store = Store::open(&args.data_dir).unwrap_or_else(|e| {
+        eprintln!("Error: {}: {}", args.data_dir.display(), e);
+        std::process::exit(1);
+    });
+    let state = match store.load_state() {
+        Ok(Some(state)) => state,
+        Ok(None) => {
+            let state = match &args.snapshot {
+                Some(path) => load_snapshot(path)
+                    .map(|snapshot| snapshot.state)
+                    .map_err(|e| e.to_string()),
+                None => load_genesis(&args.genesis)
+                    .map(|genesis| SequencerState::genesis(genesis.state))
+                    .map_err(|e| e.to_string()),
+            }
+            .unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            if let Err(e) = store.init_state(&state) {
+                eprintln!("Error: {}: {}", args.data_dir.display(), e);
+                std::process::exit(1);
+            }
+            state
+        }
+        Err(e) => {
+            eprintln!("Error: {}: {}", args.data_dir.display(), e);
+            std::process::exit(1);
+        }
+    };
+    println!(
+        "Starting at batch {}, state hash: {}",
+        state.batch_number,
+        hash_state(&state.accounts)
+    );
+
+    let mempool = Arc::new(Mutex::new(Mempool::new(args.mempool_capacity)));
+    let ledger = Arc::new(RwLock::new(Ledger::new(&state)));
+    let (updates, _) = broadcast::channel(1024);
+    let store = Arc::new(store);
+    let sequencer = Sequencer::new(
+        RpcClient::new(args.rpc_url),
+        args.platform_id,
+        store.clone(),
+        state,
+        mempool.clone(),
+        ledger.clone(),
+        updates.clone(),
+        BatchLimits {
+            interval: Duration::from_millis(args.batch_interval_ms),
+            max_txs: args.max_batch_txs,
+            max_ramp_txs: args.max_ramp_txs,
+            max_l1_messages: args.max_l1_messages,
+            snapshot_interval: args.snapshot_interval,
+        },
+    );
+    // The runner and the L1 client are blocking
+    std::thread::spawn(move || sequencer.run());
+
+    let ws_listen = args.ws_listen.unwrap_or_else(|| {
+        let mut address = args.listen;
+        address.set_port(args.listen.port() + 1);
+        address
+    });
+    let rpc_listener = bind(args.listen).await;
+    let ws_listener = bind(ws_listen).await;
+    println!("JSON-RPC on {}, PubSub on {}", args.listen, ws_listen);
+
+    let pubsub = axum::serve(
+        ws_listener,
+        pubsub::router(ledger.clone(), store.clone(), updates),
+    );
+    let rpc = axum::serve(rpc_listener, rpc::router(mempool, ledger, store));
+    let (rpc, pubsub) = tokio::join!(rpc, pubsub);
+    rpc.unwrap();
+    pubsub.unwrap();
+}
+
+async fn bind(address: SocketAddr) -> tokio::net::TcpListener {
+    tokio::net::TcpListener::bind(address)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}: {}", address, e);
+            std::process::exit(1);
+        })
+}
@@ -0,0 +1,160 @@
+use solana_sdk::{
+    signature::Signature,
+    transaction::{SanitizedTransaction, Transaction, TransactionError},
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MempoolError {
+    InvalidSignature,
+    Sanitize(TransactionError),
+    Duplicate(Signature),
+    Full,
+}
+
+impl fmt::Display for MempoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MempoolError::InvalidSignature => write!(f, "invalid transaction signature"),
+            MempoolError::Sanitize(err) => write!(f, "invalid transaction: {}", err),
+            MempoolError::Duplicate(signature) => {
+                write!(f, "transaction {} is already pending", signature)
+            }
+            MempoolError::Full => write!(f, "mempool is full"),
+        }
+    }
+}
+
+impl std::error::Error for MempoolError {}
+
+/// Signed transactions waiting for the next batch, in arrival order.
+pub struct Mempool {
+    capacity: usize,
+    transactions: VecDeque<Transaction>,
+    /// Signatures of the queued transactions, and of the taken ones until
+    /// they are released
+    signatures: HashSet<Signature>,
+}
+
+impl Mempool {
+    pub fn new(capacity: usize) -> Self {
+        Mempool {
+            capacity,
+            transactions: VecDeque::new(),
+            signatures: HashSet::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.transactions.len()
+    }
+
+    /// Queues a transaction. It must be well formed, the runner drops it
+    /// otherwise, and correctly signed, as the runner does not verify
+    /// signatures.
+    pub fn push(&mut self, transaction: Transaction) -> Result<Signature, MempoolError> {
+        SanitizedTransaction::try_from_legacy_transaction(transaction.clone(), &HashSet::new())
+            .map_err(MempoolError::Sanitize)?;
+        if transaction.verify().is_err() {
+            return Err(MempoolError::InvalidSignature);
+        }
+
+        let signature = transaction.signatures[0];
+        if self.signatures.contains(&signature) {
+            return Err(MempoolError::Duplicate(signature));
+        }
+        if self.transactions.len() >= self.capacity {
+            return Err(MempoolError::Full);
+        }
+
+        self.signatures.insert(signature);
+        self.transactions.push_back(transaction);
+        Ok(signature)
+    }
+
+    /// Removes up to `max` transactions, oldest first. They cannot be queued
+    /// again until [`Mempool::release`], so a transaction is not executed
+    /// twice while its batch is being produced.
+    pub fn take(&mut self, max: usize) -> Vec<Transaction> {
+        let count = max.min(self.transactions.len());
+        self.transactions.drain(..count).collect()
+    }
+
+    /// Queues taken transactions again, in front and in order, when their batch
+    /// could not be produced. They may exceed the capacity.
+    pub fn restore(&mut self, transactions: Vec<Transaction>) {
+        for transaction in transactions.into_iter().rev() {
+            self.transactions.push_front(transaction);
+        }
+    }
+
+    /// Forgets taken transactions, once their batch is committed and the
+    /// store knows them.
+    pub fn release<'a>(&mut self, signatures: impl IntoIterator<Item = &'a Signature>) {
+        for signature in signatures {
+            self.signatures.remove(signature);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+    };
+
+    use super::*;
+
+    fn transfer(payer: &Keypair, lamports: u64) -> Transaction {
+        Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &payer.pubkey(),
+                &Pubkey::new_unique(),
+                lamports,
+            )],
+            Some(&payer.pubkey()),
+            &[payer],
+            Hash::default(),
+        )
+    }
+
+    #[test]
+    fn test_mempool() {
+        let payer = Keypair::new();
+        let mut mempool = Mempool::new(2);
+
+        let first = transfer(&payer, 1);
+        mempool.push(first.clone()).unwrap();
+        assert_eq!(
+            mempool.push(first.clone()),
+            Err(MempoolError::Duplicate(first.signatures[0]))
+        );
+
+        let mut forged = transfer(&payer, 2);
+        forged.signatures[0] = Signature::default();
+        assert_eq!(mempool.push(forged), Err(MempoolError::InvalidSignature));
+
+        mempool.push(transfer(&payer, 3)).unwrap();
+        assert_eq!(mempool.push(transfer(&payer, 4)), Err(MempoolError::Full));
+
+        let taken = mempool.take(1);
+        assert_eq!(taken, vec![first.clone()]);
+        assert_eq!(mempool.len(), 1);
+        // Still known while its batch is produced
+        assert_eq!(
+            mempool.push(first.clone()),
+            Err(MempoolError::Duplicate(first.signatures[0]))
+        );
+        // Back first when its batch failed
+        mempool.restore(taken.clone());
+        assert_eq!(mempool.len(), 2);
+        assert_eq!(mempool.take(2)[0], first);
+
+        // The store rejects it once the batch is committed
+        mempool.release(taken.iter().map(|transaction| &transaction.signatures[0]));
+        mempool.push(first).unwrap();
+    }
+}
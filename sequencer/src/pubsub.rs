@@ -0,0 +1,385 @@
+//! Solana PubSub subset over WebSocket. Notifications are sent when a batch
+//! reaches the stage of the subscription: executed for `processed`, proven for
+//! `confirmed` and accepted by zk-bridge for `finalized`.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use solana_sdk::{account::ReadableAccount, pubkey::Pubkey, signature::Signature};
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    ledger::{Commitment, Ledger, StageUpdate},
+    rpc::{
+        config, encode_account, param, parse_pubkey, parse_signature, transaction_record,
+        with_context, AccountFilters, Filter, Request, RpcError, RpcResult, INVALID_REQUEST,
+        METHOD_NOT_FOUND,
+    },
+    store::Store,
+};
+
+struct PubSubState {
+    ledger: Arc<RwLock<Ledger>>,
+    store: Arc<Store>,
+    updates: broadcast::Sender<StageUpdate>,
+}
+
+enum Subscription {
+    Account {
+        pubkey: Pubkey,
+        encoding: Option<String>,
+        commitment: Commitment,
+    },
+    Program {
+        program_id: Pubkey,
+        encoding: Option<String>,
+        filters: AccountFilters,
+        commitment: Commitment,
+    },
+    Signature {
+        signature: Signature,
+        commitment: Commitment,
+    },
+    Slot,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SubscribeConfig {
+    encoding: Option<String>,
+    #[serde(default)]
+    commitment: Commitment,
+    filters: Option<Vec<Filter>>,
+}
+
+/// Subscriptions of a connection.
+#[derive(Default)]
+struct Subscriptions {
+    next_id: u64,
+    subscriptions: HashMap<u64, Subscription>,
+}
+
+pub fn router(
+    ledger: Arc<RwLock<Ledger>>,
+    store: Arc<Store>,
+    updates: broadcast::Sender<StageUpdate>,
+) -> Router {
+    Router::new()
+        .route("/", get(upgrade))
+        .with_state(Arc::new(PubSubState {
+            ledger,
+            store,
+            updates,
+        }))
+}
+
+async fn upgrade(State(state): State<Arc<PubSubState>>, ws: WebSocketUpgrade) -> Response {
+    ws.on_upgrade(move |socket| handle_socket(socket, state))
+}
+
+async fn handle_socket(mut socket: WebSocket, state: Arc<PubSubState>) {
+    let mut updates = state.updates.subscribe();
+    let mut subscriptions = Subscriptions::default();
+
+    loop {
+        let messages = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    subscriptions.handle_request(&state.ledger, &state.store, &text)
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    let root = state.ledger.read().unwrap().finalized_slot;
+                    subscriptions.notifications(&update, root)
+                }
+                // Too slow to keep up, the client resubscribes
+                Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => break,
+            },
+        };
+
+        for message in messages {
+            if socket
+                .send(Message::Text(message.to_string().into()))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
+}
+
+impl Subscriptions {
+    /// Handles a (un)subscribe request, returns the response and any
+    /// notification already due.
+    fn handle_request(&mut self, ledger: &RwLock<Ledger>, store: &Store, text: &str) -> Vec<Value> {
+        let request: Request = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = RpcError::new(INVALID_REQUEST, e.to_string());
+                return vec![json!({ "jsonrpc": "2.0", "id": null, "error": error })];
+            }
+        };
+
+        let mut messages = Vec::new();
+        let response = match self.dispatch(ledger, store, &request, &mut messages) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request.id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": request.id, "error": error }),
+        };
+        messages.insert(0, response);
+        messages
+    }
+
+    fn dispatch(
+        &mut self,
+        ledger: &RwLock<Ledger>,
+        store: &Store,
+        request: &Request,
+        due: &mut Vec<Value>,
+    ) -> RpcResult {
+        let params = &request.params;
+        let subscription = match request.method.as_str() {
+            "accountSubscribe" => {
+                let config: SubscribeConfig = config(params, 1)?;
+                Subscription::Account {
+                    pubkey: parse_pubkey(&param::<String>(params, 0)?)?,
+                    encoding: config.encoding,
+                    commitment: config.commitment,
+                }
+            }
+            "programSubscribe" => {
+                let config: SubscribeConfig = config(params, 1)?;
+                Subscription::Program {
+                    program_id: parse_pubkey(&param::<String>(params, 0)?)?,
+                    encoding: config.encoding,
+                    filters: AccountFilters::new(config.filters)?,
+                    commitment: config.commitment,
+                }
+            }
+            "signatureSubscribe" => {
+                let config: SubscribeConfig = config(params, 1)?;
+                let signature = parse_signature(&param::<String>(params, 0)?)?;
+                // Already at the stage, notified right away
+                if let Some(record) = transaction_record(store, &signature)? {
+                    if ledger.read().unwrap().commitment(record.slot) >= config.commitment {
+                        let id = self.next_id();
+                        let value = json!({ "err": record.receipt.status.as_ref().err() });
+                        due.push(notification(
+                            "signatureNotification",
+                            id,
+                            with_context(record.slot, value),
+                        ));
+                        return Ok(json!(id));
+                    }
+                }
+                Subscription::Signature {
+                    signature,
+                    commitment: config.commitment,
+                }
+            }
+            "slotSubscribe" => Subscription::Slot,
+            "accountUnsubscribe"
+            | "programUnsubscribe"
+            | "signatureUnsubscribe"
+            | "slotUnsubscribe" => {
+                let id: u64 = param(params, 0)?;
+                return Ok(json!(self.subscriptions.remove(&id).is_some()));
+            }
+            method => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("Method not found: {}", method),
+                ))
+            }
+        };
+
+        // Fail on an unsupported encoding now rather than when notifying
+        if let Subscription::Account { encoding, .. } | Subscription::Program { encoding, .. } =
+            &subscription
+        {
+            encode_account(&Default::default(), encoding.as_deref())?;
+        }
+
+        let id = self.next_id();
+        self.subscriptions.insert(id, subscription);
+        Ok(json!(id))
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Notifications for a batch reaching a stage. `root` is the last
+    /// finalized slot.
+    fn notifications(&mut self, update: &StageUpdate, root: u64) -> Vec<Value> {
+        let batch = &update.batch;
+        let mut messages = Vec::new();
+        let mut done = Vec::new();
+
+        for (id, subscription) in &self.subscriptions {
+            match subscription {
+                Subscription::Account {
+                    pubkey,
+                    encoding,
+                    commitment,
+                } if *commitment == update.commitment => {
+                    for (_, account) in batch.accounts.iter().filter(|(key, _)| key == pubkey) {
+                        let value = encode_account(account, encoding.as_deref()).unwrap();
+                        messages.push(notification(
+                            "accountNotification",
+                            *id,
+                            with_context(batch.slot, value),
+                        ));
+                    }
+                }
+                Subscription::Program {
+                    program_id,
+                    encoding,
+                    filters,
+                    commitment,
+                } if *commitment == update.commitment => {
+                    for (pubkey, account) in batch.accounts.iter().filter(|(_, account)| {
+                        account.owner() == program_id && filters.matches(account)
+                    }) {
+                        let value = json!({
+                            "pubkey": pubkey.to_string(),
+                            "account": encode_account(account, encoding.as_deref()).unwrap(),
+                        });
+                        messages.push(notification(
+                            "programNotification",
+                            *id,
+                            with_context(batch.slot, value),
+                        ));
+                    }
+                }
+                Subscription::Signature {
+                    signature,
+                    commitment,
+                } if *commitment == update.commitment => {
+                    if let Some(receipt) = batch
+                        .receipts
+                        .iter()
+                        .find(|receipt| receipt.signature == *signature)
+                    {
+                        let value = json!({ "err": receipt.status.as_ref().err() });
+                        messages.push(notification(
+                            "signatureNotification",
+                            *id,
+                            with_context(batch.slot, value),
+                        ));
+                        done.push(*id);
+                    }
+                }
+                Subscription::Slot if update.commitment == Commitment::Processed => {
+                    messages.push(notification(
+                        "slotNotification",
+                        *id,
+                        json!({ "parent": batch.slot - 1, "root": root, "slot": batch.slot }),
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        // Signature subscriptions end with their notification
+        for id in done {
+            self.subscriptions.remove(&id);
+        }
+        messages
+    }
+}
+
+fn notification(method: &str, subscription: u64, result: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": { "result": result, "subscription": subscription },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rollup_test_utils::temp_path;
+    use solana_sdk::{account::AccountSharedData, signature::Signature};
+    use svm_runner_types::{RollupState, TransactionReceipt};
+
+    use super::*;
+    use crate::{ledger::BatchUpdate, store::SequencerState};
+
+    #[test]
+    fn test_notifications() {
+        let ledger = RwLock::new(Ledger::new(&SequencerState::genesis(RollupState(vec![]))));
+        let dir = temp_path("sequencer-pubsub");
+        let store = Store::open(&dir).unwrap();
+        let program_id = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let signature = Signature::new_unique();
+        let mut subscriptions = Subscriptions::default();
+
+        let requests = [
+            json!([pubkey.to_string(), { "commitment": "processed" }]),
+            json!([program_id.to_string(), { "commitment": "confirmed" }]),
+            json!([signature.to_string()]),
+        ];
+        for (method, params) in ["accountSubscribe", "programSubscribe", "signatureSubscribe"]
+            .iter()
+            .zip(requests)
+        {
+            let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+            let messages = subscriptions.handle_request(&ledger, &store, &request.to_string());
+            assert!(messages[0]["result"].is_u64());
+        }
+
+        let batch = Arc::new(BatchUpdate {
+            slot: 1,
+            accounts: vec![(pubkey, AccountSharedData::new(5, 0, &program_id))],
+            receipts: vec![TransactionReceipt::dropped(
+                signature,
+                solana_sdk::transaction::TransactionError::AccountNotFound,
+            )],
+        });
+        let stage = |commitment| StageUpdate {
+            commitment,
+            batch: batch.clone(),
+        };
+
+        let messages = subscriptions.notifications(&stage(Commitment::Processed), 0);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["method"], "accountNotification");
+        assert_eq!(messages[0]["params"]["result"]["value"]["lamports"], 5);
+
+        let messages = subscriptions.notifications(&stage(Commitment::Confirmed), 0);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["method"], "programNotification");
+
+        let messages = subscriptions.notifications(&stage(Commitment::Finalized), 1);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["method"], "signatureNotification");
+        assert_eq!(
+            messages[0]["params"]["result"]["value"]["err"],
+            "AccountNotFound"
+        );
+        // Removed once notified
+        assert!(subscriptions
+            .notifications(&stage(Commitment::Finalized), 1)
+            .is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,691 @@
+//! Solana JSON-RPC subset over the rollup, so `@solana/web3.js`, Anchor and the
+//! `solana` CLI can use it as a cluster. A batch is a slot.
+//!
+//! Reads are served from the state after the last batch whatever commitment
+//! is asked, transaction statuses report the stage of their batch.
+
+use axum::{extract::State, routing::post, Json, Router};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount},
+    hash::Hash,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Signature,
+    transaction::{Transaction, TransactionError},
+};
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex, RwLock},
+};
+use svm_runner::runner;
+use svm_runner_types::{ExecutionInput, TransactionReceipt};
+
+use crate::{
+    ledger::{Commitment, Ledger, TransactionRecord},
+    mempool::Mempool,
+    store::Store,
+};
+
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const SEND_TRANSACTION_PREFLIGHT_FAILURE: i64 = -32002;
+
+struct RpcState {
+    mempool: Arc<Mutex<Mempool>>,
+    ledger: Arc<RwLock<Ledger>>,
+    store: Arc<Store>,
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct RpcError {
+    code: i64,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+}
+
+impl RpcError {
+    pub(crate) fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub(crate) fn invalid_params(message: impl Into<String>) -> Self {
+        RpcError::new(INVALID_PARAMS, message)
+    }
+}
+
+pub(crate) type RpcResult = Result<Value, RpcError>;
+
+#[derive(Deserialize)]
+pub(crate) struct Request {
+    #[serde(default)]
+    pub(crate) id: Value,
+    pub(crate) method: String,
+    #[serde(default)]
+    pub(crate) params: Value,
+}
+
+pub fn router(
+    mempool: Arc<Mutex<Mempool>>,
+    ledger: Arc<RwLock<Ledger>>,
+    store: Arc<Store>,
+) -> Router {
+    Router::new()
+        .route("/", post(handle))
+        .with_state(Arc::new(RpcState {
+            mempool,
+            ledger,
+            store,
+        }))
+}
+
+async fn handle(State(state): State<Arc<RpcState>>, Json(request): Json<Value>) -> Json<Value> {
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => {
+            return Json(json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": RpcError::new(INVALID_REQUEST, e.to_string()),
+            }))
+        }
+    };
+
+    // Simulations run the SVM, keep them off the async workers
+    let id = request.id;
+    let result =
+        tokio::task::spawn_blocking(move || dispatch(&state, &request.method, &request.params))
+            .await
+            .unwrap();
+
+    Json(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    })
+}
+
+fn dispatch(state: &RpcState, method: &str, params: &Value) -> RpcResult {
+    match method {
+        "sendTransaction" => send_transaction(state, params),
+        "simulateTransaction" => simulate_transaction(state, params),
+        "getAccountInfo" => get_account_info(state, params),
+        "getMultipleAccounts" => get_multiple_accounts(state, params),
+        "getBalance" => get_balance(state, params),
+        "getLatestBlockhash" => get_latest_blockhash(state),
+        "getSignatureStatuses" => get_signature_statuses(state, params),
+        "getProgramAccounts" => get_program_accounts(state, params),
+        "getTransaction" => get_transaction(state, params),
+        "getSlot" | "getBlockHeight" => Ok(json!(state.ledger.read().unwrap().slot)),
+        "getMinimumBalanceForRentExemption" => {
+            let size: usize = param(params, 0)?;
+            Ok(json!(Rent::default().minimum_balance(size)))
+        }
+        "getHealth" => Ok(json!("ok")),
+        "getVersion" => Ok(json!({ "solana-core": "2.2.0", "feature-set": 0 })),
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AccountConfig {
+    encoding: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ProgramAccountsConfig {
+    encoding: Option<String>,
+    filters: Option<Vec<Filter>>,
+    #[serde(default)]
+    with_context: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum Filter {
+    DataSize(u64),
+    Memcmp(Memcmp),
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Memcmp {
+    offset: usize,
+    bytes: String,
+    encoding: Option<String>,
+}
+
+/// Filters of `getProgramAccounts` and `programSubscribe`, with the memcmp
+/// bytes decoded.
+pub(crate) struct AccountFilters {
+    data_sizes: Vec<u64>,
+    memcmps: Vec<(usize, Vec<u8>)>,
+}
+
+impl AccountFilters {
+    pub(crate) fn new(filters: Option<Vec<Filter>>) -> Result<Self, RpcError> {
+        let mut data_sizes = Vec::new();
+        let mut memcmps = Vec::new();
+        for filter in filters.unwrap_or_default() {
+            match filter {
+                Filter::DataSize(size) => data_sizes.push(size),
+                Filter::Memcmp(memcmp) => {
+                    let bytes = match memcmp.encoding.as_deref().unwrap_or("base58") {
+                        "base58" => bs58::decode(&memcmp.bytes).into_vec().ok(),
+                        "base64" => BASE64.decode(&memcmp.bytes).ok(),
+                        _ => None,
+                    }
+                    .ok_or_else(|| RpcError::invalid_params("invalid memcmp bytes"))?;
+                    memcmps.push((memcmp.offset, bytes));
+                }
+            }
+        }
+        Ok(AccountFilters {
+            data_sizes,
+            memcmps,
+        })
+    }
+
+    pub(crate) fn matches(&self, account: &AccountSharedData) -> bool {
+        let data = account.data();
+        self.data_sizes
+            .iter()
+            .all(|size| data.len() as u64 == *size)
+            && self.memcmps.iter().all(|(offset, bytes)| {
+                data.get(*offset..offset + bytes.len())
+                    .is_some_and(|data| data == bytes.as_slice())
+            })
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SendTransactionConfig {
+    encoding: Option<String>,
+    #[serde(default)]
+    skip_preflight: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SimulateTransactionConfig {
+    encoding: Option<String>,
+    #[serde(default)]
+    sig_verify: bool,
+    #[serde(default)]
+    replace_recent_blockhash: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TransactionConfig {
+    encoding: Option<String>,
+}
+
+fn send_transaction(state: &RpcState, params: &Value) -> RpcResult {
+    let config: SendTransactionConfig = config(params, 1)?;
+    let transaction = decode_transaction(&param::<String>(params, 0)?, config.encoding)?;
+    if !state
+        .ledger
+        .read()
+        .unwrap()
+        .is_valid_blockhash(&transaction.message.recent_blockhash)
+    {
+        return Err(transaction_error(TransactionError::BlockhashNotFound));
+    }
+
+    if !config.skip_preflight {
+        let receipt = simulate(state, transaction.clone());
+        if let Err(err) = &receipt.status {
+            let mut error = RpcError::new(
+                SEND_TRANSACTION_PREFLIGHT_FAILURE,
+                format!("Transaction simulation failed: {}", err),
+            );
+            error.data = Some(simulation_result(&receipt));
+            return Err(error);
+        }
+    }
+
+    // Checked under the lock, since the sequencer commits a batch before
+    // releasing its transactions from the mempool. A dropped transaction had
+    // no effect, so it can be sent again once its cause is fixed.
+    let mut mempool = state.mempool.lock().unwrap();
+    let signature = transaction.signatures.first().copied().unwrap_or_default();
+    if transaction_record(&state.store, &signature)?
+        .is_some_and(|record| !record.receipt.was_dropped())
+    {
+        return Err(transaction_error(TransactionError::AlreadyProcessed));
+    }
+    let signature = mempool
+        .push(transaction)
+        .map_err(|e| RpcError::new(SEND_TRANSACTION_PREFLIGHT_FAILURE, e.to_string()))?;
+
+    Ok(json!(signature.to_string()))
+}
+
+fn simulate_transaction(state: &RpcState, params: &Value) -> RpcResult {
+    let config: SimulateTransactionConfig = config(params, 1)?;
+    let mut transaction = decode_transaction(&param::<String>(params, 0)?, config.encoding)?;
+
+    if config.sig_verify && config.replace_recent_blockhash {
+        return Err(RpcError::invalid_params(
+            "sigVerify may not be used with replaceRecentBlockhash",
+        ));
+    }
+    if config.replace_recent_blockhash {
+        transaction.message.recent_blockhash = state.ledger.read().unwrap().latest_blockhash();
+    }
+
+    let receipt = if config.sig_verify && transaction.verify().is_err() {
+        TransactionReceipt::dropped(
+            transaction.signatures.first().copied().unwrap_or_default(),
+            TransactionError::SignatureFailure,
+        )
+    } else {
+        simulate(state, transaction)
+    };
+
+    let slot = state.ledger.read().unwrap().slot;
+    Ok(with_context(slot, simulation_result(&receipt)))
+}
+
+fn get_account_info(state: &RpcState, params: &Value) -> RpcResult {
+    let pubkey = parse_pubkey(&param::<String>(params, 0)?)?;
+    let config: AccountConfig = config(params, 1)?;
+
+    let ledger = state.ledger.read().unwrap();
+    let account = ledger
+        .accounts
+        .get(&pubkey)
+        .map(|account| encode_account(account, config.encoding.as_deref()))
+        .transpose()?;
+
+    Ok(with_context(ledger.slot, json!(account)))
+}
+
+fn get_multiple_accounts(state: &RpcState, params: &Value) -> RpcResult {
+    let pubkeys: Vec<String> = param(params, 0)?;
+    let config: AccountConfig = config(params, 1)?;
+
+    let ledger = state.ledger.read().unwrap();
+    let accounts = pubkeys
+        .iter()
+        .map(|pubkey| {
+            ledger
+                .accounts
+                .get(&parse_pubkey(pubkey)?)
+                .map(|account| encode_account(account, config.encoding.as_deref()))
+                .transpose()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(with_context(ledger.slot, json!(accounts)))
+}
+
+fn get_balance(state: &RpcState, params: &Value) -> RpcResult {
+    let pubkey = parse_pubkey(&param::<String>(params, 0)?)?;
+    let ledger = state.ledger.read().unwrap();
+    let lamports = ledger
+        .accounts
+        .get(&pubkey)
+        .map_or(0, |account| account.lamports());
+
+    Ok(with_context(ledger.slot, json!(lamports)))
+}
+
+fn get_latest_blockhash(state: &RpcState) -> RpcResult {
+    let ledger = state.ledger.read().unwrap();
+    let value = json!({
+        "blockhash": ledger.latest_blockhash().to_string(),
+        "lastValidBlockHeight": ledger.last_valid_slot(),
+    });
+
+    Ok(with_context(ledger.slot, value))
+}
+
+fn get_signature_statuses(state: &RpcState, params: &Value) -> RpcResult {
+    let signatures: Vec<String> = param(params, 0)?;
+
+    let signatures = signatures
+        .iter()
+        .map(|signature| parse_signature(signature))
+        .collect::<Result<Vec<_>, RpcError>>()?;
+    let records = signatures
+        .iter()
+        .map(|signature| transaction_record(&state.store, signature))
+        .collect::<Result<Vec<_>, RpcError>>()?;
+
+    let ledger = state.ledger.read().unwrap();
+    let statuses: Vec<_> = records
+        .into_iter()
+        .map(|record| {
+            record.map(|record| {
+                let commitment = ledger.commitment(record.slot);
+                json!({
+                    "slot": record.slot,
+                    // As on L1, finalized transactions have no confirmation count
+                    "confirmations": (commitment != Commitment::Finalized).then_some(0),
+                    "err": record.receipt.status.as_ref().err(),
+                    "status": status(&record.receipt.status),
+                    "confirmationStatus": commitment.as_str(),
+                })
+            })
+        })
+        .collect();
+
+    Ok(with_context(ledger.slot, json!(statuses)))
+}
+
+fn get_program_accounts(state: &RpcState, params: &Value) -> RpcResult {
+    let program_id = parse_pubkey(&param::<String>(params, 0)?)?;
+    let config: ProgramAccountsConfig = config(params, 1)?;
+
+    let filters = AccountFilters::new(config.filters)?;
+
+    let ledger = state.ledger.read().unwrap();
+    let accounts = ledger
+        .accounts
+        .iter()
+        .filter(|(_, account)| *account.owner() == program_id && filters.matches(account))
+        .map(|(pubkey, account)| {
+            Ok(json!({
+                "pubkey": pubkey.to_string(),
+                "account": encode_account(account, config.encoding.as_deref())?,
+            }))
+        })
+        .collect::<Result<Vec<_>, RpcError>>()?;
+
+    if config.with_context {
+        Ok(with_context(ledger.slot, json!(accounts)))
+    } else {
+        Ok(json!(accounts))
+    }
+}
+
+fn get_transaction(state: &RpcState, params: &Value) -> RpcResult {
+    let signature = parse_signature(&param::<String>(params, 0)?)?;
+    let config: TransactionConfig = config(params, 1)?;
+
+    let Some(record) = transaction_record(&state.store, &signature)? else {
+        return Ok(Value::Null);
+    };
+
+    let transaction = match config.encoding.as_deref().unwrap_or("json") {
+        "json" => transaction_json(&record.transaction),
+        "base64" => json!([
+            BASE64.encode(bincode::serialize(&record.transaction).unwrap()),
+            "base64"
+        ]),
+        "base58" => json!([
+            bs58::encode(bincode::serialize(&record.transaction).unwrap()).into_string(),
+            "base58"
+        ]),
+        encoding => {
+            return Err(RpcError::invalid_params(format!(
+                "unsupported encoding: {}",
+                encoding
+            )))
+        }
+    };
+    let receipt = &record.receipt;
+
+    Ok(json!({
+        "slot": record.slot,
+        "blockTime": null,
+        "transaction": transaction,
+        "meta": {
+            "err": receipt.status.as_ref().err(),
+            "status": status(&receipt.status),
+            "fee": receipt.fee,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": [],
+            "logMessages": receipt.logs,
+            "preTokenBalances": [],
+            "postTokenBalances": [],
+            "rewards": [],
+            "computeUnitsConsumed": receipt.compute_units_consumed,
+        },
+    }))
+}
+
+/// Executes a transaction on the current state without committing it.
+fn simulate(state: &RpcState, transaction: Transaction) -> TransactionReceipt {
+    let signature = transaction.signatures.first().copied().unwrap_or_default();
+    let input = {
+        let ledger = state.ledger.read().unwrap();
+        ExecutionInput {
+            batch_number: ledger.slot,
+            accounts: ledger.rollup_state(),
+            txs: vec![transaction],
+            ramp_txs_start_hash: Hash::default(),
+            ramp_txs: vec![],
+            l1_messages_start_hash: Hash::default(),
+            l1_messages: vec![],
+        }
+    };
+
+    match runner(&input) {
+        Ok(mut output) => output.receipts.remove(0),
+        Err(err) => TransactionReceipt::dropped(signature, err),
+    }
+}
+
+fn simulation_result(receipt: &TransactionReceipt) -> Value {
+    json!({
+        "err": receipt.status.as_ref().err(),
+        "logs": receipt.logs,
+        "accounts": null,
+        "unitsConsumed": receipt.compute_units_consumed,
+        "returnData": null,
+    })
+}
+
+pub(crate) fn with_context(slot: u64, value: Value) -> Value {
+    json!({ "context": { "slot": slot }, "value": value })
+}
+
+fn status(status: &Result<(), TransactionError>) -> Value {
+    match status {
+        Ok(()) => json!({ "Ok": null }),
+        Err(err) => json!({ "Err": err }),
+    }
+}
+
+fn transaction_error(err: TransactionError) -> RpcError {
+    RpcError::new(SEND_TRANSACTION_PREFLIGHT_FAILURE, err.to_string())
+}
+
+/// Account in the `UiAccount` layout. Data is base64 unless asked otherwise.
+pub(crate) fn encode_account(account: &AccountSharedData, encoding: Option<&str>) -> RpcResult {
+    let data = account.data();
+    let data = match encoding.unwrap_or("base64") {
+        "base64" => json!([BASE64.encode(data), "base64"]),
+        "base58" => json!([bs58::encode(data).into_string(), "base58"]),
+        "base64+zstd" => json!([
+            BASE64.encode(zstd::encode_all(data, 0).unwrap()),
+            "base64+zstd"
+        ]),
+        encoding => {
+            return Err(RpcError::invalid_params(format!(
+                "unsupported encoding: {}",
+                encoding
+            )))
+        }
+    };
+
+    Ok(json!({
+        "lamports": account.lamports(),
+        "data": data,
+        "owner": account.owner().to_string(),
+        "executable": account.executable(),
+        "rentEpoch": account.rent_epoch(),
+        "space": account.data().len(),
+    }))
+}
+
+/// Transaction in the `json` encoding of `getTransaction`.
+fn transaction_json(transaction: &Transaction) -> Value {
+    let message = &transaction.message;
+    json!({
+        "signatures": transaction.signatures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        "message": {
+            "accountKeys": message.account_keys.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            "header": {
+                "numRequiredSignatures": message.header.num_required_signatures,
+                "numReadonlySignedAccounts": message.header.num_readonly_signed_accounts,
+                "numReadonlyUnsignedAccounts": message.header.num_readonly_unsigned_accounts,
+            },
+            "recentBlockhash": message.recent_blockhash.to_string(),
+            "instructions": message.instructions.iter().map(|instruction| json!({
+                "programIdIndex": instruction.program_id_index,
+                "accounts": instruction.accounts,
+                "data": bs58::encode(&instruction.data).into_string(),
+                "stackHeight": null,
+            })).collect::<Vec<_>>(),
+        },
+    })
+}
+
+/// Decodes a wire transaction, base58 unless asked otherwise as on L1.
+fn decode_transaction(encoded: &str, encoding: Option<String>) -> Result<Transaction, RpcError> {
+    let bytes = match encoding.as_deref().unwrap_or("base58") {
+        "base58" => bs58::decode(encoded).into_vec().ok(),
+        "base64" => BASE64.decode(encoded).ok(),
+        encoding => {
+            return Err(RpcError::invalid_params(format!(
+                "unsupported encoding: {}",
+                encoding
+            )))
+        }
+    }
+    .ok_or_else(|| RpcError::invalid_params("invalid transaction encoding"))?;
+
+    if bytes.len() > PACKET_DATA_SIZE {
+        return Err(RpcError::invalid_params(format!(
+            "transaction is larger than {} bytes",
+            PACKET_DATA_SIZE
+        )));
+    }
+    bincode::deserialize(&bytes)
+        .map_err(|e| RpcError::invalid_params(format!("invalid transaction: {}", e)))
+}
+
+pub(crate) fn param<T: DeserializeOwned>(params: &Value, index: usize) -> Result<T, RpcError> {
+    let value = params
+        .get(index)
+        .ok_or_else(|| RpcError::invalid_params(format!("missing param {}", index)))?;
+    serde_json::from_value(value.clone())
+        .map_err(|e| RpcError::invalid_params(format!("invalid param {}: {}", index, e)))
+}
+
+/// Optional config object of a method, defaults when missing or null.
+pub(crate) fn config<T: DeserializeOwned + Default>(
+    params: &Value,
+    index: usize,
+) -> Result<T, RpcError> {
+    match params.get(index) {
+        None | Some(Value::Null) => Ok(T::default()),
+        Some(_) => param(params, index),
+    }
+}
+
+pub(crate) fn parse_pubkey(s: &str) -> Result<Pubkey, RpcError> {
+    Pubkey::from_str(s).map_err(|e| RpcError::invalid_params(format!("invalid pubkey: {}", e)))
+}
+
+pub(crate) fn parse_signature(s: &str) -> Result<Signature, RpcError> {
+    Signature::from_str(s)
+        .map_err(|e| RpcError::invalid_params(format!("invalid signature: {}", e)))
+}
+
+/// Executed transaction, from the store as the ledger only keeps accounts
+pub(crate) fn transaction_record(
+    store: &Store,
+    signature: &Signature,
+) -> Result<Option<TransactionRecord>, RpcError> {
+    store
+        .transaction(signature)
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rollup_test_utils::temp_path;
+    use solana_sdk::account::Account;
+    use svm_runner_types::RollupState;
+
+    use super::*;
+    use crate::store::SequencerState;
+
+    #[test]
+    fn test_get_program_accounts() {
+        let program_id = Pubkey::new_unique();
+        let (pk_first, pk_second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let account = |data: Vec<u8>| -> AccountSharedData {
+            Account {
+                lamports: 1,
+                data,
+                owner: program_id,
+                executable: false,
+                rent_epoch: 0,
+            }
+            .into()
+        };
+        let state = SequencerState::genesis(RollupState(vec![
+            (pk_first, account(vec![1, 2, 3])),
+            (pk_second, account(vec![1, 5, 3, 4])),
+            (
+                Pubkey::new_unique(),
+                AccountSharedData::new(1, 0, &Pubkey::new_unique()),
+            ),
+        ]));
+        let dir = temp_path("sequencer-rpc");
+        let state = RpcState {
+            mempool: Arc::new(Mutex::new(Mempool::new(1))),
+            ledger: Arc::new(RwLock::new(Ledger::new(&state))),
+            store: Arc::new(Store::open(&dir).unwrap()),
+        };
+
+        let params = json!([program_id.to_string(), { "encoding": "base64" }]);
+        let accounts = dispatch(&state, "getProgramAccounts", &params).unwrap();
+        assert_eq!(accounts.as_array().unwrap().len(), 2);
+
+        let params = json!([
+            program_id.to_string(),
+            { "filters": [{ "memcmp": { "offset": 1, "bytes": bs58::encode([5]).into_string() } }] }
+        ]);
+        let accounts = dispatch(&state, "getProgramAccounts", &params).unwrap();
+        assert_eq!(
+            accounts,
+            json!([{
+                "pubkey": pk_second.to_string(),
+                "account": encode_account(&account(vec![1, 5, 3, 4]), None).unwrap(),
+            }])
+        );
+
+        let params = json!([program_id.to_string(), { "filters": [{ "dataSize": 3 }] }]);
+        let accounts = dispatch(&state, "getProgramAccounts", &params).unwrap();
+        assert_eq!(accounts[0]["pubkey"], json!(pk_first.to_string()));
+
+        let balance = dispatch(&state, "getBalance", &json!([pk_first.to_string()])).unwrap();
+        assert_eq!(balance["value"], json!(1));
+        let error = dispatch(&state, "getBalance", &json!(["invalid"])).unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,263 @@
+use bridge_client::{get_account, get_messages, get_ramp_txs, platform_address, Platform};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use std::{
+    error::Error,
+    sync::{Arc, Mutex, RwLock},
+    thread,
+    time::{Duration, Instant},
+};
+use svm_runner::{config_hash, runner};
+use svm_runner_types::{hash_l1_messages, hash_ramp_txs, hash_state, BatchJob, ExecutionInput};
+use tokio::sync::broadcast;
+
+use crate::{
+    ledger::{push_blockhash, Ledger, StageUpdate, TransactionRecord},
+    mempool::Mempool,
+    store::{written_accounts, SequencerState, Store},
+};
+
+/// How often the mempool is checked for a full batch
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct BatchLimits {
+    /// A batch is cut at least this often, if there is anything to put in it
+    pub interval: Duration,
+    /// A batch is cut as soon as the mempool holds this many transactions
+    pub max_txs: usize,
+    pub max_ramp_txs: u64,
+    pub max_l1_messages: u64,
+    /// A full snapshot is written every this many batches, never if 0
+    pub snapshot_interval: u64,
+}
+
+/// Cuts batches from the mempool and the L1 queues of a platform.
+pub struct Sequencer {
+    client: RpcClient,
+    platform_id: Pubkey,
+    store: Arc<Store>,
+    state: SequencerState,
+    mempool: Arc<Mutex<Mempool>>,
+    ledger: Arc<RwLock<Ledger>>,
+    /// Batches reaching a stage, for subscriptions
+    updates: broadcast::Sender<StageUpdate>,
+    limits: BatchLimits,
+}
+
+impl Sequencer {
+    pub fn new(
+        client: RpcClient,
+        platform_id: Pubkey,
+        store: Arc<Store>,
+        state: SequencerState,
+        mempool: Arc<Mutex<Mempool>>,
+        ledger: Arc<RwLock<Ledger>>,
+        updates: broadcast::Sender<StageUpdate>,
+        limits: BatchLimits,
+    ) -> Self {
+        Sequencer {
+            client,
+            platform_id,
+            store,
+            state,
+            mempool,
+            ledger,
+            updates,
+            limits,
+        }
+    }
+
+    /// Produces batches forever.
+    pub fn run(mut self) {
+        let mut last_batch = Instant::now();
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let full = self.mempool.lock().unwrap().len() >= self.limits.max_txs;
+            if !full && last_batch.elapsed() < self.limits.interval {
+                continue;
+            }
+            last_batch = Instant::now();
+
+            let platform: Platform =
+                match get_account(&self.client, &platform_address(&self.platform_id)) {
+                    Ok(platform) => platform,
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        continue;
+                    }
+                };
+            self.advance_stages(&platform);
+
+            let batch_number = self.state.batch_number;
+            match self.produce_batch(&platform) {
+                Ok(true) => println!(
+                    "Batch {}: {}",
+                    batch_number,
+                    hash_state(&self.state.accounts)
+                ),
+                Ok(false) => {}
+                Err(e) => eprintln!("Error: batch {}: {}", batch_number, e),
+            }
+        }
+    }
+
+    /// Confirms the batches whose proof is written, and finalizes the ones
+    /// proven on L1. Batch `n` is slot `n + 1`.
+    fn advance_stages(&mut self, platform: &Platform) {
+        let mut ledger = self.ledger.write().unwrap();
+        let mut proven = ledger.confirmed_slot;
+        while proven < ledger.slot && self.store.proof_path(proven).exists() {
+            proven += 1;
+        }
+
+        let mut updates = ledger.confirm(proven);
+        updates.extend(ledger.finalize(platform.batch_number));
+        for update in updates {
+            // No receivers is fine, nobody subscribed
+            let _ = self.updates.send(update);
+        }
+    }
+
+    /// Executes the next batch and persists it, returns false if there was
+    /// nothing to execute.
+    pub fn produce_batch(&mut self, platform: &Platform) -> Result<bool, Box<dyn Error>> {
+        // The platform would reject the proofs of the batches
+        if Hash::new_from_array(platform.config_hash) != config_hash() {
+            return Err(format!(
+                "runner config hash {} does not match the platform config hash {}",
+                config_hash(),
+                Hash::new_from_array(platform.config_hash)
+            )
+            .into());
+        }
+
+        let ramp_count = platform
+            .ramp_tail
+            .saturating_sub(self.state.ramp_head)
+            .min(self.limits.max_ramp_txs);
+        let ramp_txs: Vec<_> = get_ramp_txs(
+            &self.client,
+            &self.platform_id,
+            self.state.ramp_head,
+            ramp_count,
+        )?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+        let message_count = platform
+            .message_tail
+            .saturating_sub(self.state.message_head)
+            .min(self.limits.max_l1_messages);
+        let l1_messages: Vec<_> = get_messages(
+            &self.client,
+            &self.platform_id,
+            self.state.message_head,
+            message_count,
+        )?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        // Taken last, so they are not lost if L1 cannot be read
+        let txs = self.mempool.lock().unwrap().take(self.limits.max_txs);
+        let result = self.execute_batch(ExecutionInput {
+            batch_number: self.state.batch_number,
+            accounts: self.state.accounts.clone(),
+            txs: txs.clone(),
+            ramp_txs_start_hash: self.state.ramp_txs_hash,
+            ramp_txs,
+            l1_messages_start_hash: self.state.message_queue_hash,
+            l1_messages,
+        });
+        let mut mempool = self.mempool.lock().unwrap();
+        match &result {
+            // Nothing was committed, they go in the next attempt
+            Err(_) => mempool.restore(txs),
+            // Committed, the store answers for them from now on
+            Ok(_) => mempool.release(txs.iter().map(|tx| &tx.signatures[0])),
+        }
+        result
+    }
+
+    /// Executes `input`, the next batch, and persists it.
+    fn execute_batch(&mut self, mut input: ExecutionInput) -> Result<bool, Box<dyn Error>> {
+        // A transaction executed in an earlier batch sent again is a replay,
+        // the mempool only knows the ones not committed yet. A dropped one is
+        // executed, and its record replaced.
+        let mut txs = Vec::with_capacity(input.txs.len());
+        for tx in std::mem::take(&mut input.txs) {
+            let replay = self
+                .store
+                .transaction(&tx.signatures[0])?
+                .is_some_and(|record| !record.receipt.was_dropped());
+            if !replay {
+                txs.push(tx);
+            }
+        }
+        input.txs = txs;
+        if input.txs.is_empty() && input.ramp_txs.is_empty() && input.l1_messages.is_empty() {
+            return Ok(false);
+        }
+
+        let output = runner(&input)?;
+
+        for receipt in &output.receipts {
+            if let Err(err) = &receipt.status {
+                println!("Transaction {} failed: {}", receipt.signature, err);
+            }
+        }
+
+        let mut blockhashes = self.state.blockhashes.clone();
+        push_blockhash(&mut blockhashes, &hash_state(&output.state));
+        let state = SequencerState {
+            batch_number: self.state.batch_number + 1,
+            accounts: output.state,
+            ramp_head: self.state.ramp_head + input.ramp_txs.len() as u64,
+            ramp_txs_hash: hash_ramp_txs(self.state.ramp_txs_hash, &input.ramp_txs),
+            message_head: self.state.message_head + input.l1_messages.len() as u64,
+            message_queue_hash: hash_l1_messages(self.state.message_queue_hash, &input.l1_messages),
+            blockhashes,
+        };
+        let records: Vec<_> = input
+            .txs
+            .iter()
+            .zip(&output.receipts)
+            .map(|(transaction, receipt)| TransactionRecord {
+                slot: state.batch_number,
+                transaction: transaction.clone(),
+                receipt: receipt.clone(),
+            })
+            .collect();
+        let written = written_accounts(&self.state.accounts, &state.accounts);
+        // From the store, not the input, so the prover can check the input
+        let pre_state_hash = self
+            .store
+            .pre_state_root(input.batch_number)?
+            .ok_or_else(|| format!("no state hash before batch {}", input.batch_number))?;
+        let job = BatchJob {
+            pre_state_hash,
+            input,
+        };
+        self.store
+            .commit_batch(&job, &self.state, &state, &written, &records)?;
+        let update = self
+            .ledger
+            .write()
+            .unwrap()
+            .apply_batch(&state, written, &output.receipts);
+        let _ = self.updates.send(update);
+        self.state = state;
+
+        let interval = self.limits.snapshot_interval;
+        if interval != 0 && self.state.batch_number % interval == 0 {
+            // The database holds the state already, a missing snapshot is not fatal
+            match self.store.write_snapshot(&self.state) {
+                Ok(path) => println!("Snapshot: {}", path.display()),
+                Err(e) => eprintln!("Error: snapshot: {}", e),
+            }
+        }
+
+        Ok(true)
+    }
+}
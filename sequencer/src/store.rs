@@ -0,0 +1,544 @@
+//! Persistence of the sequencer.
+//!
+//! `<data_dir>/rollup.redb` is an embedded database holding the current
+//! accounts, the accounts written by each batch, the state root after each
+//! batch and the executed transactions with their receipts. A batch is
+//! committed in a single database transaction, so after a crash the sequencer
+//! resumes from the last committed batch.
+//!
+//! `<data_dir>/jobs/batch-<n>.bin` holds the bincode [`BatchJob`] of batch
+//! `n`, for the prover: its input and the state hash committed before it. It is written as `batch-<n>.pending` first and
+//! only renamed once the batch is committed, so the prover never sees the job
+//! of a batch lost in a crash. The prover writes the proof of batch `n` to
+//! `<data_dir>/jobs/batch-<n>.proof.bin`.
+//!
+//! `<data_dir>/snapshots/snapshot-<n>.bin` is a full snapshot of the state
+//! before batch `n`, the bincode encoding of [`Snapshot`]:
+//!
+//! | Field        | Encoding                                                         |
+//! |--------------|------------------------------------------------------------------|
+//! | `version`    | `u32`, [`SNAPSHOT_VERSION`]                                      |
+//! | `state_hash` | 32 bytes, `hash_state` of `state.accounts`                       |
+//! | `state`      | [`SequencerState`], whose `accounts` is a bincode [`RollupState`] |
+//!
+//! [`load_snapshot`] reads one back and checks its state hash.
+
+use redb::{Database, ReadableTable, TableDefinition};
+use rollup_storage::{create_database, impl_from_redb_error, write_atomic};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use solana_sdk::{account::AccountSharedData, hash::Hash, pubkey::Pubkey, signature::Signature};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+use svm_runner_types::{hash_state, BatchJob, RollupState};
+
+use crate::ledger::TransactionRecord;
+
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Position in the rollup state to `(Pubkey, AccountSharedData)`. The order
+/// of the accounts is part of the state hash, so it is kept as is.
+const ACCOUNTS: TableDefinition<u64, &[u8]> = TableDefinition::new("accounts");
+/// Batch number to the accounts it wrote, closed ones being empty
+const DIFFS: TableDefinition<u64, &[u8]> = TableDefinition::new("diffs");
+/// Batch number to the state hash after it
+const ROOTS: TableDefinition<u64, &[u8]> = TableDefinition::new("roots");
+/// Signature to [`TransactionRecord`]
+const TRANSACTIONS: TableDefinition<&[u8], &[u8]> = TableDefinition::new("transactions");
+/// [`StateMeta`] under [`META_STATE`], and the state hash of the genesis under
+/// [`META_GENESIS_ROOT`]
+const META: TableDefinition<&str, &[u8]> = TableDefinition::new("meta");
+const META_STATE: &str = "state";
+const META_GENESIS_ROOT: &str = "genesis_root";
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(PathBuf, io::Error),
+    Database(redb::Error),
+    Corrupted(String),
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            StoreError::Database(err) => write!(f, "database error: {}", err),
+            StoreError::Corrupted(err) => write!(f, "corrupted store: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl_from_redb_error!(StoreError);
+
+/// Everything the sequencer needs to produce the next batch.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SequencerState {
+    /// Number of the next batch
+    pub batch_number: u64,
+    pub accounts: RollupState,
+    /// Index of the first ramp tx of the platform not in a batch yet
+    pub ramp_head: u64,
+    /// Ramp txs hash of the queue before `ramp_head`
+    pub ramp_txs_hash: Hash,
+    /// Index of the first L1 message not in a batch yet
+    pub message_head: u64,
+    /// Message queue hash of the queue before `message_head`
+    pub message_queue_hash: Hash,
+    /// Blockhashes of the last batches, oldest first
+    pub blockhashes: VecDeque<Hash>,
+}
+
+impl SequencerState {
+    pub fn genesis(accounts: RollupState) -> Self {
+        let blockhashes = VecDeque::from([hash_state(&accounts)]);
+        SequencerState {
+            batch_number: 0,
+            accounts,
+            ramp_head: 0,
+            ramp_txs_hash: Hash::default(),
+            message_head: 0,
+            message_queue_hash: Hash::default(),
+            blockhashes,
+        }
+    }
+}
+
+/// [`SequencerState`] without its accounts
+#[derive(Deserialize, Serialize)]
+struct StateMeta {
+    batch_number: u64,
+    ramp_head: u64,
+    ramp_txs_hash: Hash,
+    message_head: u64,
+    message_queue_hash: Hash,
+    blockhashes: VecDeque<Hash>,
+}
+
+impl From<&SequencerState> for StateMeta {
+    fn from(state: &SequencerState) -> Self {
+        StateMeta {
+            batch_number: state.batch_number,
+            ramp_head: state.ramp_head,
+            ramp_txs_hash: state.ramp_txs_hash,
+            message_head: state.message_head,
+            message_queue_hash: state.message_queue_hash,
+            blockhashes: state.blockhashes.clone(),
+        }
+    }
+}
+
+/// Full sequencer state, see the module documentation for its format.
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Snapshot {
+    pub version: u32,
+    pub state_hash: Hash,
+    pub state: SequencerState,
+}
+
+pub struct Store {
+    dir: PathBuf,
+    db: Database,
+}
+
+impl Store {
+    pub fn open(dir: &Path) -> Result<Self, StoreError> {
+        for subdir in ["jobs", "snapshots"] {
+            let path = dir.join(subdir);
+            fs::create_dir_all(&path).map_err(|e| StoreError::Io(path, e))?;
+        }
+        let db = create_database(&dir.join("rollup.redb"), |txn| {
+            txn.open_table(ACCOUNTS)?;
+            txn.open_table(DIFFS)?;
+            txn.open_table(ROOTS)?;
+            txn.open_table(TRANSACTIONS)?;
+            txn.open_table(META)?;
+            Ok(())
+        })?;
+
+        let store = Store {
+            dir: dir.to_path_buf(),
+            db,
+        };
+        store.recover_jobs()?;
+        Ok(store)
+    }
+
+    /// Completes the jobs of the batches committed before a crash, and removes
+    /// those of the batches that were not.
+    fn recover_jobs(&self) -> Result<(), StoreError> {
+        let dir = self.dir.join("jobs");
+        let entries = fs::read_dir(&dir).map_err(|e| StoreError::Io(dir.clone(), e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| StoreError::Io(dir.clone(), e))?.path();
+            let batch_number = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("batch-"))
+                .and_then(|name| name.strip_suffix(".pending"))
+                .and_then(|number| number.parse().ok());
+            let Some(batch_number) = batch_number else {
+                continue;
+            };
+            let result = if self.root(batch_number)?.is_some() {
+                fs::rename(&path, self.job_path(batch_number))
+            } else {
+                fs::remove_file(&path)
+            };
+            result.map_err(|e| StoreError::Io(path, e))?;
+        }
+        Ok(())
+    }
+
+    /// State after the last committed batch, `None` if nothing was stored yet.
+    pub fn load_state(&self) -> Result<Option<SequencerState>, StoreError> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(META)?;
+        let Some(meta) = table.get(META_STATE)? else {
+            return Ok(None);
+        };
+        let meta: StateMeta = deserialize(meta.value())?;
+        drop(table);
+
+        let mut accounts = Vec::new();
+        for entry in txn.open_table(ACCOUNTS)?.iter()? {
+            let (position, account) = entry?;
+            if position.value() != accounts.len() as u64 {
+                return Err(StoreError::Corrupted(format!(
+                    "no account at position {}",
+                    accounts.len()
+                )));
+            }
+            accounts.push(deserialize(account.value())?);
+        }
+
+        let accounts = RollupState(accounts);
+        if let Some(batch_number) = meta.batch_number.checked_sub(1) {
+            if self.root(batch_number)? != Some(hash_state(&accounts)) {
+                return Err(StoreError::Corrupted(format!(
+                    "accounts do not match the root of batch {}",
+                    batch_number
+                )));
+            }
+        }
+
+        Ok(Some(SequencerState {
+            batch_number: meta.batch_number,
+            accounts,
+            ramp_head: meta.ramp_head,
+            ramp_txs_hash: meta.ramp_txs_hash,
+            message_head: meta.message_head,
+            message_queue_hash: meta.message_queue_hash,
+            blockhashes: meta.blockhashes,
+        }))
+    }
+
+    /// Stores the state the sequencer starts from, the genesis or a snapshot.
+    pub fn init_state(&self, state: &SequencerState) -> Result<(), StoreError> {
+        let txn = self.db.begin_write()?;
+        {
+            let mut table = txn.open_table(ACCOUNTS)?;
+            table.retain(|_, _| false)?;
+            for (position, account) in state.accounts.0.iter().enumerate() {
+                table.insert(position as u64, serialize(account).as_slice())?;
+            }
+            // What `load_state` checks the accounts against, and the jobs
+            // start from
+            let root = hash_state(&state.accounts);
+            match state.batch_number.checked_sub(1) {
+                Some(batch_number) => {
+                    txn.open_table(ROOTS)?.insert(batch_number, root.as_ref())?;
+                }
+                None => {
+                    txn.open_table(META)?
+                        .insert(META_GENESIS_ROOT, root.as_ref())?;
+                }
+            }
+            let meta = serialize(&StateMeta::from(state));
+            txn.open_table(META)?.insert(META_STATE, meta.as_slice())?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Moves from `previous` to `state` in a single database transaction, then
+    /// publishes the job of the batch. A crash before the commit leaves the
+    /// previous state, and the batch is produced again with a new job.
+    /// `written` are the [`written_accounts`] of the batch.
+    pub fn commit_batch(
+        &self,
+        job: &BatchJob,
+        previous: &SequencerState,
+        state: &SequencerState,
+        written: &[(Pubkey, AccountSharedData)],
+        transactions: &[TransactionRecord],
+    ) -> Result<(), StoreError> {
+        let batch_number = job.input.batch_number;
+        let pending_path = self.job_path(batch_number).with_extension("pending");
+        write_atomic(&pending_path, &serialize(job))
+            .map_err(|e| StoreError::Io(pending_path.clone(), e))?;
+
+        let txn = self.db.begin_write()?;
+        {
+            let (before, after) = (&previous.accounts.0, &state.accounts.0);
+            let mut table = txn.open_table(ACCOUNTS)?;
+            for (position, account) in after.iter().enumerate() {
+                if before.get(position) != Some(account) {
+                    table.insert(position as u64, serialize(account).as_slice())?;
+                }
+            }
+            for position in after.len()..before.len() {
+                table.remove(position as u64)?;
+            }
+
+            txn.open_table(DIFFS)?
+                .insert(batch_number, serialize(&written).as_slice())?;
+            let root = hash_state(&state.accounts);
+            txn.open_table(ROOTS)?.insert(batch_number, root.as_ref())?;
+
+            let mut table = txn.open_table(TRANSACTIONS)?;
+            for record in transactions {
+                let signature = record.receipt.signature;
+                table.insert(signature.as_ref(), serialize(record).as_slice())?;
+            }
+
+            let meta = serialize(&StateMeta::from(state));
+            txn.open_table(META)?.insert(META_STATE, meta.as_slice())?;
+        }
+        txn.commit()?;
+
+        // Completed by `recover_jobs` after a crash here
+        let job_path = self.job_path(batch_number);
+        fs::rename(&pending_path, &job_path).map_err(|e| StoreError::Io(job_path, e))?;
+        Ok(())
+    }
+
+    pub fn transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<TransactionRecord>, StoreError> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(TRANSACTIONS)?;
+        let record = table.get(signature.as_ref())?;
+        record.map(|record| deserialize(record.value())).transpose()
+    }
+
+    /// State hash after a batch.
+    pub fn root(&self, batch_number: u64) -> Result<Option<Hash>, StoreError> {
+        let txn = self.db.begin_read()?;
+        let table = txn.open_table(ROOTS)?;
+        let root = table.get(batch_number)?;
+        root.map(|root| {
+            <[u8; 32]>::try_from(root.value())
+                .map(Hash::new_from_array)
+                .map_err(|_| StoreError::Corrupted(format!("root of batch {}", batch_number)))
+        })
+        .transpose()
+    }
+
+    /// State hash before a batch, the root of the previous one or of the genesis.
+    pub fn pre_state_root(&self, batch_number: u64) -> Result<Option<Hash>, StoreError> {
+        let Some(previous) = batch_number.checked_sub(1) else {
+            let txn = self.db.begin_read()?;
+            let table = txn.open_table(META)?;
+            let root = table.get(META_GENESIS_ROOT)?;
+            return root
+                .map(|root| {
+                    <[u8; 32]>::try_from(root.value())
+                        .map(Hash::new_from_array)
+                        .map_err(|_| StoreError::Corrupted("genesis root".to_string()))
+                })
+                .transpose();
+        };
+        self.root(previous)
+    }
+
+    /// Writes a full snapshot of `state`, returns its path.
+    pub fn write_snapshot(&self, state: &SequencerState) -> Result<PathBuf, StoreError> {
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            state_hash: hash_state(&state.accounts),
+            state: state.clone(),
+        };
+        let path = self
+            .dir
+            .join("snapshots")
+            .join(format!("snapshot-{}.bin", state.batch_number));
+        write_atomic(&path, &serialize(&snapshot)).map_err(|e| StoreError::Io(path.clone(), e))?;
+        Ok(path)
+    }
+
+    pub fn job_path(&self, batch_number: u64) -> PathBuf {
+        self.dir
+            .join("jobs")
+            .join(format!("batch-{}.bin", batch_number))
+    }
+
+    /// Written by the prover once the proof of the batch is generated
+    pub fn proof_path(&self, batch_number: u64) -> PathBuf {
+        self.dir
+            .join("jobs")
+            .join(format!("batch-{}.proof.bin", batch_number))
+    }
+}
+
+/// Reads a snapshot written by [`Store::write_snapshot`].
+pub fn load_snapshot(path: &Path) -> Result<Snapshot, StoreError> {
+    let bytes = fs::read(path).map_err(|e| StoreError::Io(path.to_path_buf(), e))?;
+    let snapshot: Snapshot = deserialize(&bytes)?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(StoreError::Corrupted(format!(
+            "unsupported snapshot version {}",
+            snapshot.version
+        )));
+    }
+    if hash_state(&snapshot.state.accounts) != snapshot.state_hash {
+        return Err(StoreError::Corrupted(
+            "snapshot accounts do not match its state hash".to_string(),
+        ));
+    }
+    Ok(snapshot)
+}
+
+/// Accounts of `after` that differ from `before`, and the closed ones as empty
+/// accounts.
+pub fn written_accounts(
+    before: &RollupState,
+    after: &RollupState,
+) -> Vec<(Pubkey, AccountSharedData)> {
+    let before: HashMap<&Pubkey, &AccountSharedData> = before
+        .0
+        .iter()
+        .map(|(pubkey, account)| (pubkey, account))
+        .collect();
+    let mut diff: Vec<(Pubkey, AccountSharedData)> = after
+        .0
+        .iter()
+        .filter(|(pubkey, account)| before.get(pubkey) != Some(&account))
+        .cloned()
+        .collect();
+    let after: HashMap<&Pubkey, &AccountSharedData> = after
+        .0
+        .iter()
+        .map(|(pubkey, account)| (pubkey, account))
+        .collect();
+    diff.extend(
+        before
+            .keys()
+            .filter(|pubkey| !after.contains_key(*pubkey))
+            .map(|pubkey| (**pubkey, AccountSharedData::default())),
+    );
+    diff
+}
+
+fn serialize<T: Serialize>(value: &T) -> Vec<u8> {
+    bincode::serialize(value).unwrap()
+}
+
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, StoreError> {
+    bincode::deserialize(bytes).map_err(|e| StoreError::Corrupted(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rollup_test_utils::temp_path;
+    use solana_sdk::{transaction::Transaction, transaction::TransactionError};
+    use svm_runner_types::{ExecutionInput, TransactionReceipt};
+
+    use super::*;
+
+    fn account(lamports: u64) -> (Pubkey, AccountSharedData) {
+        (
+            Pubkey::new_unique(),
+            AccountSharedData::new(lamports, 0, &Pubkey::default()),
+        )
+    }
+
+    #[test]
+    fn test_store() {
+        let dir = temp_path("sequencer-store");
+        let genesis =
+            SequencerState::genesis(RollupState(vec![account(1), account(2), account(3)]));
+
+        let store = Store::open(&dir).unwrap();
+        assert!(store.load_state().unwrap().is_none());
+        store.init_state(&genesis).unwrap();
+
+        // Batch 0 writes the second account, closes the third and creates one
+        let mut state = genesis.clone();
+        state.batch_number = 1;
+        state.ramp_head = 2;
+        state.accounts.0[1].1 = AccountSharedData::new(5, 0, &Pubkey::default());
+        let closed = state.accounts.0.pop().unwrap().0;
+        state.accounts.0.push(account(6));
+        let job = BatchJob {
+            pre_state_hash: store.pre_state_root(0).unwrap().unwrap(),
+            input: ExecutionInput {
+                batch_number: 0,
+                accounts: genesis.accounts.clone(),
+                txs: vec![],
+                ramp_txs_start_hash: Hash::default(),
+                ramp_txs: vec![],
+                l1_messages_start_hash: Hash::default(),
+                l1_messages: vec![],
+            },
+        };
+        assert_eq!(job.pre_state_hash, hash_state(&genesis.accounts));
+        let signature = Signature::new_unique();
+        let record = TransactionRecord {
+            slot: 1,
+            transaction: Transaction::default(),
+            receipt: TransactionReceipt::dropped(signature, TransactionError::AccountNotFound),
+        };
+        let written = written_accounts(&genesis.accounts, &state.accounts);
+        store
+            .commit_batch(&job, &genesis, &state, &written, &[record])
+            .unwrap();
+        drop(store);
+
+        // As after a restart
+        let store = Store::open(&dir).unwrap();
+        let loaded = store.load_state().unwrap().unwrap();
+        assert_eq!(loaded.batch_number, 1);
+        assert_eq!(loaded.ramp_head, 2);
+        assert_eq!(loaded.accounts.0, state.accounts.0);
+        assert!(store.job_path(0).exists());
+        drop(store);
+
+        // A crash after committing batch 0, and another while producing batch 1
+        let pending = |batch_number: u64| {
+            let path = dir.join("jobs").join(format!("batch-{}.bin", batch_number));
+            path.with_extension("pending")
+        };
+        fs::rename(dir.join("jobs/batch-0.bin"), pending(0)).unwrap();
+        fs::write(pending(1), b"lost").unwrap();
+        let store = Store::open(&dir).unwrap();
+        assert!(store.job_path(0).exists());
+        assert!(!pending(0).exists());
+        assert!(!pending(1).exists());
+        assert!(!store.job_path(1).exists());
+
+        let txn = store.db.begin_read().unwrap();
+        let table = txn.open_table(DIFFS).unwrap();
+        let diff: Vec<(Pubkey, AccountSharedData)> =
+            deserialize(table.get(0).unwrap().unwrap().value()).unwrap();
+        assert_eq!(diff.len(), 3);
+        assert!(diff.contains(&state.accounts.0[1]));
+        assert!(diff.contains(&state.accounts.0[2]));
+        assert!(diff.contains(&(closed, AccountSharedData::default())));
+        assert_eq!(store.root(0).unwrap(), Some(hash_state(&state.accounts)));
+        assert_eq!(store.pre_state_root(1).unwrap(), store.root(0).unwrap());
+        assert_eq!(store.root(1).unwrap(), None);
+        assert_eq!(store.transaction(&signature).unwrap().unwrap().slot, 1);
+
+        let path = store.write_snapshot(&loaded).unwrap();
+        let snapshot = load_snapshot(&path).unwrap();
+        assert_eq!(snapshot.state.batch_number, 1);
+        assert_eq!(snapshot.state.accounts.0, state.accounts.0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}